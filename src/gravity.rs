@@ -17,14 +17,61 @@ pub mod field;
 /// Utility functions
 pub mod util;
 
+/// Pure math core of the gravity fields
+pub mod math;
+
+/// Helper node integrating gravity over substeps
+pub mod integrator;
+
+/// Resource baking the gravity of a static scene
+pub mod baker;
+
+/// Editor gizmos drawing the gravity of the fields
+#[cfg(feature = "editor")]
+pub mod editor;
+
+use godot::{
+    builtin::{Aabb, Rect2, Vector2, Vector3, real},
+    classes::Node,
+    obj::Gd,
+};
+
 /// Type used to define priority level
 pub type Level = i32;
 
 /// Type used to define collision masks
 pub type Mask = u32;
 
+/// Type used to define gravity channels
+pub type Channel = u32;
+
+/// Convert a gravity channel into the collision bit it maps to.
+/// Channels range from 0 to 31, mapping to the collision layers 1 to 32.
+#[inline]
+pub const fn channel_mask(channel: Channel) -> Mask {
+    1 << if channel < Mask::BITS {
+        channel
+    } else {
+        Mask::BITS - 1
+    }
+}
+
+/// Associate a vector type with the type of its bounding boxes
+pub trait WithBounds {
+    /// Axis-aligned bounding box type
+    type Bounds;
+}
+
+impl WithBounds for Vector2 {
+    type Bounds = Rect2;
+}
+
+impl WithBounds for Vector3 {
+    type Bounds = Aabb;
+}
+
 /// Trait to implement a gravity field
-pub trait Field<V> {
+pub trait Field<V: WithBounds> {
     /// Get the priority level of the gravity field
     fn level(&self) -> Level;
 
@@ -33,17 +80,147 @@ pub trait Field<V> {
 
     /// Get the UP direction for the given position in global space.
     fn global_up(&self, position: &V) -> V;
+
+    /// Get the center of the gravity field in global space.
+    fn global_center(&self) -> V;
+
+    /// Check if the gravity field applies to the given node.
+    #[inline]
+    fn affects(&self, _node: &Gd<Node>) -> bool {
+        true
+    }
+
+    /// Get the strength of the gravity for the given position in global space.
+    #[inline]
+    fn strength(&self, _position: &V) -> real {
+        1.0
+    }
+
+    /// Get the gravity channel of the field.
+    #[inline]
+    fn channel(&self) -> Channel {
+        0
+    }
+
+    /// Get the bounding box of the gravity field in global space.
+    /// Return `None` if the field is not bounded.
+    #[inline]
+    fn bounds(&self) -> Option<V::Bounds> {
+        None
+    }
+
+    /// Get the velocity in global space of a point at the given position
+    /// rotating along with the field, for fields spinning around an axis.
+    #[inline]
+    fn angular_velocity(&self, _position: &V) -> V
+    where
+        V: Default,
+    {
+        V::default()
+    }
 }
 
 #[macro_export]
 macro_rules! export_gravity_up {
-    ( $gravity_field_type:ty => $vector:ty ) => {
+    ( $gravity_field_type:ty => $vector:ty $( ; $( $extra_signals:tt )* )? ) => {
         #[godot_api]
         impl $gravity_field_type {
             #[func]
             pub fn get_up_direction(&self, position: $vector) -> $vector {
                 self.global_up(&position)
             }
+
+            /// Get the UP direction for a position in the local space of the field
+            #[func]
+            pub fn get_local_up(&self, position: $vector) -> $vector {
+                self.local_up(&position)
+            }
+
+            /// Get the priority level of the field.
+            /// Named apart from `get_level` which is the getter of the exported
+            /// level of most fields, while some fields derive it from others.
+            #[func]
+            pub fn get_field_level(&self) -> $crate::gravity::Level {
+                self.level()
+            }
+
+            /// Set the gravity channel, the collision layer of the area
+            /// is replaced by the bit matching the channel.
+            #[func]
+            pub fn set_channel(&mut self, channel: $crate::gravity::Channel) {
+                self.channel = channel.min($crate::gravity::Mask::BITS - 1);
+                let layer = $crate::gravity::channel_mask(self.channel);
+                self.base_mut().set_collision_layer(layer);
+            }
+
+            /// Emitted when a body enters the field, with the UP direction at its position
+            #[signal]
+            fn gravity_entered(body: Gd<Node>, up: $vector);
+
+            /// Emitted when a body exits the field, with the UP direction at its position
+            #[signal]
+            fn gravity_exited(body: Gd<Node>, up: $vector);
+
+            /// Forward the body signals of the area as gravity signals
+            fn connect_gravity_signals(&mut self) {
+                let entered = self.base().callable("on_gravity_body_entered");
+                let exited = self.base().callable("on_gravity_body_exited");
+                self.base_mut().connect("body_entered", &entered);
+                self.base_mut().connect("body_exited", &exited);
+            }
+
+            #[func]
+            fn on_gravity_body_entered(&mut self, body: Gd<Node>) {
+                self.emit_gravity_signal("gravity_entered", body);
+            }
+
+            #[func]
+            fn on_gravity_body_exited(&mut self, body: Gd<Node>) {
+                self.emit_gravity_signal("gravity_exited", body);
+            }
+
+            /// Emit a gravity signal for the body with the UP direction at its position
+            fn emit_gravity_signal(&mut self, signal: &str, body: Gd<Node>) {
+                let position = body
+                    .get("global_position")
+                    .try_to::<$vector>()
+                    .unwrap_or_default();
+                let up = self.global_up(&position);
+                self.base_mut()
+                    .emit_signal(signal, &[body.to_variant(), up.to_variant()]);
+            }
+
+            // signals specific to the field, they must be declared in the primary block
+            $( $( $extra_signals )* )?
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! export_gravity_dict {
+    ( $class_type:ty => [ $( $property:ident ),* $(,)? ] ) => {
+        #[godot_api(secondary)]
+        impl $class_type {
+            /// Snapshot the configuration into a dictionary keyed by property name
+            #[func]
+            pub fn to_dict(&self) -> Dictionary {
+                let mut data = Dictionary::new();
+                $(
+                    data.set(stringify!($property), self.base().get(stringify!($property)));
+                )*
+                data
+            }
+
+            /// Restore the configuration from a dictionary, missing keys are left unchanged.
+            /// Properties are assigned through their setters so cached data is invalidated.
+            #[func]
+            pub fn from_dict(&mut self, data: Dictionary) {
+                $(
+                    if let Some(value) = data.get(stringify!($property)) {
+                        self.base_mut().set(stringify!($property), &value);
+                    }
+                )*
+            }
         }
     };
 }
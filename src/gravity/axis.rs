@@ -14,6 +14,12 @@ pub enum Axis2D {
 
     /// Y-axis
     Y,
+
+    /// Negative X-axis
+    NegX,
+
+    /// Negative Y-axis
+    NegY,
 }
 
 /// Select an axis in 3D space
@@ -29,46 +35,101 @@ pub enum Axis3D {
 
     /// Z-axis
     Z,
+
+    /// Negative X-axis
+    NegX,
+
+    /// Negative Y-axis
+    NegY,
+
+    /// Negative Z-axis
+    NegZ,
 }
 
 impl Axis2D {
+    /// Every axis, the positive ones first
+    pub const ALL: [Self; 4] = [Self::X, Self::Y, Self::NegX, Self::NegY];
+
+    /// Snap a vector to the axis pointing the closest to its direction.
+    /// Ties are resolved in the order of `ALL`, a zero vector gives the X-axis.
+    pub fn from_vector(vector: Vector2) -> Self {
+        let mut best = (Self::X, real::NEG_INFINITY);
+        for axis in Self::ALL {
+            let alignment = vector.dot(axis.to_vector());
+            if alignment > best.1 {
+                best = (axis, alignment);
+            }
+        }
+        best.0
+    }
+
     /// To vector
     pub fn to_vector(self) -> Vector2 {
         match self {
             Self::X => Vector2::RIGHT,
             Self::Y => Vector2::UP,
+            Self::NegX => Vector2::LEFT,
+            Self::NegY => Vector2::DOWN,
         }
     }
 }
 
 impl Axis3D {
+    /// Every axis, the positive ones first
+    pub const ALL: [Self; 6] = [
+        Self::X,
+        Self::Y,
+        Self::Z,
+        Self::NegX,
+        Self::NegY,
+        Self::NegZ,
+    ];
+
+    /// Snap a vector to the axis pointing the closest to its direction.
+    /// Ties are resolved in the order of `ALL`, a zero vector gives the X-axis.
+    pub fn from_vector(vector: Vector3) -> Self {
+        let mut best = (Self::X, real::NEG_INFINITY);
+        for axis in Self::ALL {
+            let alignment = vector.dot(axis.to_vector());
+            if alignment > best.1 {
+                best = (axis, alignment);
+            }
+        }
+        best.0
+    }
+
     /// To vector
     pub fn to_vector(self) -> Vector3 {
         match self {
             Self::X => Vector3::RIGHT,
             Self::Y => Vector3::UP,
             Self::Z => Vector3::FORWARD,
+            Self::NegX => Vector3::LEFT,
+            Self::NegY => Vector3::DOWN,
+            Self::NegZ => Vector3::BACK,
         }
     }
 }
 
-/// Convert selected axis into godot-rust axis type
+/// Convert selected axis into godot-rust axis type,
+/// negative axes map to their positive counterpart.
 impl From<Axis2D> for Vector2Axis {
     fn from(value: Axis2D) -> Self {
         match value {
-            Axis2D::X => Self::X,
-            Axis2D::Y => Self::Y,
+            Axis2D::X | Axis2D::NegX => Self::X,
+            Axis2D::Y | Axis2D::NegY => Self::Y,
         }
     }
 }
 
-/// Convert selected axis into godot-rust axis type
+/// Convert selected axis into godot-rust axis type,
+/// negative axes map to their positive counterpart.
 impl From<Axis3D> for Vector3Axis {
     fn from(value: Axis3D) -> Self {
         match value {
-            Axis3D::X => Self::X,
-            Axis3D::Y => Self::Y,
-            Axis3D::Z => Self::Z,
+            Axis3D::X | Axis3D::NegX => Self::X,
+            Axis3D::Y | Axis3D::NegY => Self::Y,
+            Axis3D::Z | Axis3D::NegZ => Self::Z,
         }
     }
 }
@@ -93,3 +154,48 @@ impl From<Vector3Axis> for Axis3D {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Axis2D, Axis3D};
+    use godot::builtin::{Vector2, Vector3};
+
+    #[test]
+    fn exact_axes_snap_to_themselves() {
+        for axis in Axis2D::ALL {
+            assert_eq!(Axis2D::from_vector(axis.to_vector() * 3.0), axis);
+        }
+        for axis in Axis3D::ALL {
+            assert_eq!(Axis3D::from_vector(axis.to_vector() * 3.0), axis);
+        }
+    }
+
+    #[test]
+    fn diagonals_snap_to_the_first_axis_in_order() {
+        // Y-axis points toward negative Y in 2D
+        assert_eq!(Axis2D::from_vector(Vector2::new(1.0, -1.0)), Axis2D::X);
+        assert_eq!(Axis2D::from_vector(Vector2::new(-1.0, 1.0)), Axis2D::NegX);
+        assert_eq!(Axis2D::from_vector(Vector2::new(1.0, -1.1)), Axis2D::Y);
+
+        // Z-axis points forward, toward negative Z in 3D
+        assert_eq!(Axis3D::from_vector(Vector3::new(1.0, 1.0, 1.0)), Axis3D::X);
+        assert_eq!(
+            Axis3D::from_vector(Vector3::new(-1.0, 1.0, -1.0)),
+            Axis3D::Y
+        );
+        assert_eq!(
+            Axis3D::from_vector(Vector3::new(-1.0, -1.0, 1.0)),
+            Axis3D::NegX
+        );
+        assert_eq!(
+            Axis3D::from_vector(Vector3::new(0.1, -0.2, 1.0)),
+            Axis3D::NegZ
+        );
+    }
+
+    #[test]
+    fn zero_vector_snaps_to_x() {
+        assert_eq!(Axis2D::from_vector(Vector2::ZERO), Axis2D::X);
+        assert_eq!(Axis3D::from_vector(Vector3::ZERO), Axis3D::X);
+    }
+}
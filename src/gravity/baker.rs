@@ -0,0 +1,112 @@
+//!
+//! Resource baking the gravity of a static scene into a grid
+//!
+
+use crate::gravity::query::GravityQuery3D;
+use godot::{classes::PhysicsDirectSpaceState3D, prelude::*};
+use std::ops::DerefMut;
+
+/// Store the gravity sampled on a regular grid, which avoids performing
+/// physics queries every frame in levels whose gravity never changes.
+#[derive(GodotClass)]
+#[class(base=Resource)]
+pub struct GravityBaker3D {
+    base: Base<Resource>,
+
+    /// Region covered by the grid
+    #[export]
+    bounds: Aabb,
+
+    /// Number of cells along each axis of the grid
+    #[export]
+    resolution: Vector3i,
+
+    /// UP directions scaled by the strength at the center of each cell,
+    /// X varies first, then Y, then Z.
+    #[export]
+    cells: PackedVector3Array,
+}
+
+#[godot_api]
+impl IResource for GravityBaker3D {
+    /// Instantiate the resource
+    fn init(base: Base<Resource>) -> Self {
+        Self {
+            base,
+            bounds: Aabb::new(Vector3::ONE * -8.0, Vector3::ONE * 16.0),
+            resolution: Vector3i::new(8, 8, 8),
+            cells: PackedVector3Array::new(),
+        }
+    }
+}
+
+#[godot_api]
+impl GravityBaker3D {
+    /// Sample the gravity at the center of each cell of the grid.
+    /// Cells without gravity store a zero vector.
+    #[func]
+    pub fn bake(&mut self, mut space: Gd<PhysicsDirectSpaceState3D>, query: Gd<GravityQuery3D>) {
+        let query = query.bind();
+        let (resolution, cell_size) = (self.cell_counts(), self.cell_size());
+
+        let mut cells = PackedVector3Array::new();
+        for z in 0..resolution[2] {
+            for y in 0..resolution[1] {
+                for x in 0..resolution[0] {
+                    let index = Vector3::new(x as real, y as real, z as real);
+                    let center = self.bounds.position + (index + Vector3::ONE * 0.5) * cell_size;
+                    let up = query
+                        .gravity_direction(space.deref_mut(), &center)
+                        .map_or(Vector3::ZERO, |(up, _)| up);
+                    cells.push(up);
+                }
+            }
+        }
+        self.cells = cells;
+    }
+
+    /// Interpolate the baked gravity at the given position between the
+    /// centers of the surrounding cells, positions outside of the bounds
+    /// use the nearest cells. Return zero if the grid is not baked.
+    #[func]
+    pub fn sample(&self, position: Vector3) -> Vector3 {
+        let resolution = self.cell_counts();
+        if self.cells.len() != resolution.iter().product::<usize>() {
+            return Vector3::ZERO;
+        }
+        let cells = self.cells.as_slice();
+        let cell =
+            |x: usize, y: usize, z: usize| cells[x + resolution[0] * (y + resolution[1] * z)];
+
+        // find the surrounding cells and the ratio between them along each axis
+        let coords = (position - self.bounds.position) / self.cell_size() - Vector3::ONE * 0.5;
+        let axis = |coord: real, count: usize| {
+            let coord = coord.clamp(0.0, (count - 1) as real);
+            let low = coord.floor() as usize;
+            (low, (low + 1).min(count - 1), coord - low as real)
+        };
+        let (x0, x1, tx) = axis(coords.x, resolution[0]);
+        let (y0, y1, ty) = axis(coords.y, resolution[1]);
+        let (z0, z1, tz) = axis(coords.z, resolution[2]);
+
+        // blend along X, then Y, then Z
+        let lerp_x = |y: usize, z: usize| cell(x0, y, z).lerp(cell(x1, y, z), tx);
+        let lerp_y = |z: usize| lerp_x(y0, z).lerp(lerp_x(y1, z), ty);
+        lerp_y(z0).lerp(lerp_y(z1), tz)
+    }
+}
+
+impl GravityBaker3D {
+    /// Number of cells along each axis, at least one
+    #[inline]
+    fn cell_counts(&self) -> [usize; 3] {
+        [self.resolution.x, self.resolution.y, self.resolution.z].map(|count| count.max(1) as usize)
+    }
+
+    /// Size of a single cell of the grid
+    #[inline]
+    fn cell_size(&self) -> Vector3 {
+        let [x, y, z] = self.cell_counts();
+        self.bounds.size / Vector3::new(x as real, y as real, z as real)
+    }
+}
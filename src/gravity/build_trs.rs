@@ -13,60 +13,122 @@ pub type Basis3 = Basis;
 
 /// Allow building transforms
 #[derive(Debug, Clone, Copy)]
-pub struct TransformBuilder<Rot, const R: usize, Pos, const P: usize, Trs> {
+pub struct TransformBuilder<Rot, const R: usize, Pos, const P: usize, Trs, const S: usize = 0> {
     /// Set of rotation matrices
     rotations: [Rot; R],
 
     /// Set of position vectors
     positions: [Pos; P],
 
+    /// Set of scale vectors
+    scales: [Pos; S],
+
     /// Binding for the expected output type
     phantom: PhantomData<Trs>,
 }
 
 /// Build 2D transforms
-pub type TransformBuilder2D<const R: usize, const P: usize> =
-    TransformBuilder<Basis2, R, Vector2, P, Transform2D>;
+pub type TransformBuilder2D<const R: usize, const P: usize, const S: usize = 0> =
+    TransformBuilder<Basis2, R, Vector2, P, Transform2D, S>;
 
 /// Build 3D transforms
-pub type TransformBuilder3D<const R: usize, const P: usize> =
-    TransformBuilder<Basis3, R, Vector3, P, Transform3D>;
+pub type TransformBuilder3D<const R: usize, const P: usize, const S: usize = 0> =
+    TransformBuilder<Basis3, R, Vector3, P, Transform3D, S>;
 
 impl<Rot, const R: usize, Pos, const P: usize, Trs> TransformBuilder<Rot, R, Pos, P, Trs> {
     /// Create a new builder from raw arrays
     #[inline]
     pub fn new(rotations: [Rot; R], positions: [Pos; P]) -> Self {
+        Self::with_scales(rotations, positions, [])
+    }
+}
+
+impl<Rot, const R: usize, Pos, const P: usize, Trs, const S: usize>
+    TransformBuilder<Rot, R, Pos, P, Trs, S>
+{
+    /// Create a new builder from raw arrays, including scales
+    #[inline]
+    pub fn with_scales(rotations: [Rot; R], positions: [Pos; P], scales: [Pos; S]) -> Self {
         Self {
             rotations,
             positions,
-            phantom: PhantomData::default(),
+            scales,
+            phantom: PhantomData,
         }
     }
+
+    /// Number of rotation matrices available
+    #[inline]
+    pub const fn rotation_count(&self) -> usize {
+        R
+    }
+
+    /// Number of position vectors available
+    #[inline]
+    pub const fn position_count(&self) -> usize {
+        P
+    }
+
+    /// Number of scale vectors available
+    #[inline]
+    pub const fn scale_count(&self) -> usize {
+        S
+    }
 }
 
-impl<const R: usize, const P: usize> TransformBuilder2D<R, P> {
+impl<const R: usize, const P: usize, const S: usize> TransformBuilder2D<R, P, S> {
     /// Build a transform
     #[inline]
     pub fn build(&self, index_rot: usize, index_pos: usize) -> Transform2D {
         let rot = self.rotations[index_rot];
         Transform2D::from_cols(rot[0], rot[1], self.positions[index_pos])
     }
+
+    /// Build a transform with the scale baked into the basis
+    #[inline]
+    pub fn build_scaled(
+        &self,
+        index_rot: usize,
+        index_pos: usize,
+        index_scale: usize,
+    ) -> Transform2D {
+        let rot = self.rotations[index_rot];
+        let scale = self.scales[index_scale];
+        Transform2D::from_cols(
+            rot[0] * scale.x,
+            rot[1] * scale.y,
+            self.positions[index_pos],
+        )
+    }
 }
 
-impl<const R: usize, const P: usize> TransformBuilder3D<R, P> {
+impl<const R: usize, const P: usize, const S: usize> TransformBuilder3D<R, P, S> {
     /// Build a transform
     #[inline]
     pub fn build(&self, index_rot: usize, index_pos: usize) -> Transform3D {
         Transform3D::new(self.rotations[index_rot], self.positions[index_pos])
     }
+
+    /// Build a transform with the scale baked into the basis
+    #[inline]
+    pub fn build_scaled(
+        &self,
+        index_rot: usize,
+        index_pos: usize,
+        index_scale: usize,
+    ) -> Transform3D {
+        let basis = self.rotations[index_rot] * Basis::from_scale(self.scales[index_scale]);
+        Transform3D::new(basis, self.positions[index_pos])
+    }
 }
 
 /// Implement Default for Transform builder
-impl<Rot, const R: usize, Pos, const P: usize, Trs> Default
-    for TransformBuilder<Rot, R, Pos, P, Trs>
+impl<Rot, const R: usize, Pos, const P: usize, Trs, const S: usize> Default
+    for TransformBuilder<Rot, R, Pos, P, Trs, S>
 where
     [Rot; R]: Default,
     [Pos; P]: Default,
+    [Pos; S]: Default,
 {
     /// Create a new builder from raw arrays
     #[inline]
@@ -74,6 +136,7 @@ where
         Self {
             rotations: Default::default(),
             positions: Default::default(),
+            scales: Default::default(),
             phantom: Default::default(),
         }
     }
@@ -0,0 +1,142 @@
+//!
+//! Editor gizmos drawing the gravity of the fields
+//!
+
+use crate::gravity::{query::DynGravityField3D, util::util3d::orthonormal_basis};
+use godot::{
+    classes::{
+        EditorNode3DGizmo, EditorNode3DGizmoPlugin, EditorPlugin, IEditorNode3DGizmoPlugin,
+        IEditorPlugin,
+    },
+    prelude::*,
+};
+
+/// Name of the material used to draw the arrows
+const MATERIAL: &str = "gravity_arrow";
+
+/// Number of points sampled along each axis of the field
+const SAMPLES: usize = 5;
+
+/// Half extent of the sampled region around unbounded fields
+const SAMPLE_EXTENT: real = 2.0;
+
+/// Length of the arrows drawn at each sampled point
+const ARROW_LENGTH: real = 0.4;
+
+/// Get access to the gravity field of a node, if it is one.
+/// Only the tool classes run in the editor, which are the flat,
+/// center and conic fields when the `editor` feature is enabled.
+#[inline]
+fn as_field(node: Gd<Node3D>) -> Option<DynGravityField3D> {
+    node.to_variant().try_to().ok()
+}
+
+/// Draw arrows following the gravity of the selected 3D field
+#[derive(GodotClass)]
+#[class(tool, base=EditorNode3DGizmoPlugin)]
+pub struct GravityGizmoPlugin {
+    base: Base<EditorNode3DGizmoPlugin>,
+}
+
+#[godot_api]
+impl IEditorNode3DGizmoPlugin for GravityGizmoPlugin {
+    /// Instantiate the gizmo plugin
+    fn init(base: Base<EditorNode3DGizmoPlugin>) -> Self {
+        Self { base }
+    }
+
+    fn get_gizmo_name(&self) -> GString {
+        "GravityField".into()
+    }
+
+    /// Only draw the gizmo of gravity fields
+    fn has_gizmo(&self, for_node_3d: Option<Gd<Node3D>>) -> bool {
+        for_node_3d.and_then(as_field).is_some()
+    }
+
+    /// Sample the gravity on a grid within the bounds of the field,
+    /// or around its center if it is unbounded.
+    fn redraw(&mut self, gizmo: Option<Gd<EditorNode3DGizmo>>) {
+        let Some(mut gizmo) = gizmo else {
+            return;
+        };
+        gizmo.clear();
+        let Some(node) = gizmo.get_node_3d() else {
+            return;
+        };
+        let to_local = node.get_global_transform().affine_inverse();
+        let Some(field) = as_field(node) else {
+            return;
+        };
+        let field = field.dyn_bind();
+        let bounds = field.bounds().unwrap_or_else(|| {
+            let extent = Vector3::ONE * SAMPLE_EXTENT;
+            Aabb::new(field.global_center() - extent, extent * 2.0)
+        });
+
+        // draw an arrow pointing along the pull of the gravity at each point
+        let mut lines = PackedVector3Array::new();
+        let ratio = |index: usize| (index as real + 0.5) / SAMPLES as real;
+        for i in 0..SAMPLES {
+            for j in 0..SAMPLES {
+                for k in 0..SAMPLES {
+                    let point =
+                        bounds.position + bounds.size * Vector3::new(ratio(i), ratio(j), ratio(k));
+                    let up = field.global_up(&point);
+                    if up.is_zero_approx() {
+                        continue;
+                    }
+                    let down = -up.normalized();
+                    let tip = point + down * ARROW_LENGTH;
+                    let back = tip - down * (ARROW_LENGTH * 0.25);
+                    let side =
+                        orthonormal_basis(&down, &Vector3::FORWARD).col_a() * (ARROW_LENGTH * 0.25);
+                    for (from, to) in [(point, tip), (tip, back + side), (tip, back - side)] {
+                        lines.push(to_local * from);
+                        lines.push(to_local * to);
+                    }
+                }
+            }
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+        if let Some(material) = self
+            .base_mut()
+            .get_material_ex(MATERIAL)
+            .gizmo(&gizmo)
+            .done()
+        {
+            gizmo.add_lines(&lines, &material);
+        }
+    }
+}
+
+/// Register the gravity gizmos in the editor
+#[derive(GodotClass)]
+#[class(tool, init, base=EditorPlugin)]
+pub struct GravityEditorPlugin {
+    base: Base<EditorPlugin>,
+
+    /// Gizmo plugin registered while the plugin is in the tree
+    gizmo: Option<Gd<GravityGizmoPlugin>>,
+}
+
+#[godot_api]
+impl IEditorPlugin for GravityEditorPlugin {
+    /// Register the gizmo plugin
+    fn enter_tree(&mut self) {
+        let mut gizmo = GravityGizmoPlugin::new_gd();
+        gizmo.create_material(MATERIAL, Color::from_rgb(0.3, 0.6, 1.0));
+        self.base_mut().add_node_3d_gizmo_plugin(&gizmo);
+        self.gizmo = Some(gizmo);
+    }
+
+    /// Unregister the gizmo plugin
+    fn exit_tree(&mut self) {
+        if let Some(gizmo) = self.gizmo.take() {
+            self.base_mut().remove_node_3d_gizmo_plugin(&gizmo);
+        }
+    }
+}
@@ -10,3 +10,48 @@ pub mod center;
 
 /// Define gravity fields backed by shapes
 pub mod shaped;
+
+/// Define twisting gravity fields
+pub mod twist;
+
+/// Define gravity fields following the surface of a torus
+pub mod torus_surface;
+
+/// Define gravity fields interpolated from anchor points
+pub mod anchors;
+
+/// Define flipping gravity fields
+pub mod flip;
+
+/// Define gravity fields pointing away from an axis
+pub mod axial;
+
+/// Define gravity fields following the side of a cone
+pub mod conic;
+
+/// Define gravity fields along an arbitrary direction
+pub mod directional;
+
+/// Define gravity fields pulling toward the surface of a sphere
+pub mod shell;
+
+/// Define gravity fields around a capped cylinder
+pub mod cylinder;
+
+/// Define gravity fields following a heightmap
+pub mod heightmap;
+
+/// Define gravity fields perturbing other fields with noise
+pub mod noise;
+
+/// Define gravity fields combining other fields
+pub mod composite;
+
+/// Define gravity fields following the faces of a pyramid
+pub mod pyramid;
+
+/// Define gravity fields pulling toward the faces of a slab
+pub mod slab;
+
+/// Define gravity fields around a capsule
+pub mod capsule;
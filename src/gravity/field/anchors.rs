@@ -0,0 +1,245 @@
+//!
+//! Gravity field interpolated from a set of anchor points
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Hand authored gravity direction at a given point
+    #[derive(Debug, Clone, Copy)]
+    pub(super) struct Anchor {
+        /// Position of the anchor in the local space of the field
+        pub position: Vector3,
+
+        /// UP direction at the anchor
+        pub up: Vector3,
+
+        /// Influence of the anchor
+        pub weight: real,
+    }
+
+    /// Blend the UP directions of the anchors weighted by the inverse of their
+    /// distance to the position raised to the falloff power.
+    pub(super) fn interpolate(
+        anchors: &[Anchor],
+        falloff_power: real,
+        position: &Vector3,
+    ) -> Vector3 {
+        let mut up = Vector3::ZERO;
+        for anchor in anchors {
+            let distance = position.distance_to(anchor.position);
+
+            // Right on an anchor, use its direction as is
+            if distance.is_zero_approx() {
+                up = anchor.up;
+                break;
+            }
+            up += anchor.up * (anchor.weight / distance.powf(falloff_power));
+        }
+        up.normalized_or_zero()
+    }
+
+    /// Define a gravity interpolated from a set of weighted anchor points
+    /// using inverse distance weighting.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityAnchors3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Power applied to the distance when computing the weight of each anchor
+        #[export(range = (0.0, 4.0, or_greater))]
+        falloff_power: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+
+        /// Set of anchors to interpolate
+        anchors: Vec<Anchor>,
+    }
+
+    export_gravity_up![GravityAnchors3D => Vector3];
+
+    export_gravity_dict![GravityAnchors3D => [
+        level,
+        affects_groups,
+        channel,
+        falloff_power,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityAnchors3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                falloff_power: 2.0,
+                inverted: false,
+                anchors: Vec::new(),
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    #[godot_api(secondary)]
+    impl GravityAnchors3D {
+        /// Add an anchor and return its index
+        #[func]
+        pub fn add_anchor(&mut self, position: Vector3, up: Vector3, weight: real) -> i32 {
+            self.anchors.push(Anchor {
+                position,
+                up: up.normalized_or_zero(),
+                weight: weight.max(0.0),
+            });
+            self.anchors.len() as i32 - 1
+        }
+
+        /// Remove the anchor at the given index
+        #[func]
+        pub fn remove_anchor(&mut self, index: i32) {
+            if (0..self.anchors.len() as i32).contains(&index) {
+                self.anchors.remove(index as usize);
+            } else {
+                godot_warn!("Anchor index {index} out of bounds");
+            }
+        }
+
+        /// Remove every anchor
+        #[func]
+        pub fn clear_anchors(&mut self) {
+            self.anchors.clear();
+        }
+
+        /// Get the number of anchors
+        #[func]
+        pub fn get_anchor_count(&self) -> i32 {
+            self.anchors.len() as i32
+        }
+    }
+
+    impl Field<Vector3> for GravityAnchors3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction is the inverse distance weighted blend of the anchors
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = interpolate(&self.anchors, self.falloff_power, position);
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction is the inverse distance weighted blend of the anchors
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityAnchors3D;
+
+#[cfg(test)]
+mod tests {
+    use super::inner3d::{Anchor, interpolate};
+    use godot::builtin::{Transform3D, Vector3, math::ApproxEq};
+
+    /// Two anchors of equal weight pointing in perpendicular directions
+    const ANCHORS: [Anchor; 2] = [
+        Anchor {
+            position: Vector3::new(-1.0, 0.0, 0.0),
+            up: Vector3::UP,
+            weight: 1.0,
+        },
+        Anchor {
+            position: Vector3::new(1.0, 0.0, 0.0),
+            up: Vector3::RIGHT,
+            weight: 1.0,
+        },
+    ];
+
+    #[test]
+    fn near_an_anchor_its_direction_prevails() {
+        assert_eq!(
+            interpolate(&ANCHORS, 2.0, &ANCHORS[0].position),
+            Vector3::UP
+        );
+        let near = interpolate(&ANCHORS, 2.0, &Vector3::new(0.99, 0.0, 0.0));
+        assert!(near.dot(Vector3::RIGHT) > 0.99);
+    }
+
+    #[test]
+    fn anchors_blend_evenly_at_the_midpoint() {
+        let midpoint = interpolate(&ANCHORS, 2.0, &Vector3::ZERO);
+        assert!(midpoint.approx_eq(&Vector3::new(1.0, 1.0, 0.0).normalized()));
+    }
+
+    #[test]
+    fn anchors_move_along_with_the_field() {
+        // the anchors keep their authored place once the field is moved to Y = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(0.0, 10.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(-1.0, 10.0, 0.0);
+        assert_eq!(interpolate(&ANCHORS, 2.0, &local), Vector3::UP);
+    }
+
+    #[test]
+    fn no_anchor_gives_no_direction() {
+        assert_eq!(interpolate(&[], 2.0, &Vector3::ZERO), Vector3::ZERO);
+    }
+}
@@ -0,0 +1,274 @@
+//!
+//! Axial gravity fields
+//!
+
+macro_rules! gravity_field_axial {
+    (
+        $field_type:ty where {
+            $area:ty | $area_interface:ty,
+            $vector:ty,
+            $axis:ty
+        }
+        $( extend {
+            $( $extra_field:ident : $extra_value:expr ),* $(,)?
+        } {
+            $( $extra_item:item )*
+        } )?
+    ) => {
+        // alias provided types
+        type Area = $area;
+        type Axis = $axis;
+        type Vector = $vector;
+
+        #[godot_api]
+        impl $area_interface for $field_type {
+            /// Instantiate the node
+            fn init(base: Base<Area>) -> Self {
+                Self {
+                    base,
+                    level: 0,
+                    affects_groups: Array::new(),
+                    channel: 0,
+                    axis: Axis::Y,
+                    max_distance: 0.0,
+                    falloff_distance: 0.0,
+                    inverted: false,
+                    $( $( $extra_field: $extra_value, )* )?
+                }
+            }
+
+            /// Forward the body signals once the node enters the tree
+            fn ready(&mut self) {
+                self.connect_gravity_signals();
+            }
+        }
+
+        impl Field<Vector> for $field_type {
+            /// Get the priority level
+            #[inline]
+            fn level(&self) -> Level {
+                self.level
+            }
+
+            /// Up direction points away from the selected axis.
+            fn local_up(&self, position: &Vector) -> Vector {
+                let up = flatten(position, self.axis).normalized_or_zero();
+
+                // Check if the direction should be inverted
+                if self.inverted { -up } else { up }
+            }
+
+            /// Up direction points away from the selected axis.
+            fn global_up(&self, position: &Vector) -> Vector {
+                global_direction(self, position)
+            }
+
+            /// Center is the origin of the area
+            #[inline]
+            fn global_center(&self) -> Vector {
+                global_center(self)
+            }
+
+            /// Only affect the nodes in the selected groups
+            #[inline]
+            fn affects(&self, node: &Gd<Node>) -> bool {
+                in_any_group(&self.affects_groups, node)
+            }
+
+            /// Get the gravity channel
+            #[inline]
+            fn channel(&self) -> Channel {
+                self.channel
+            }
+
+            /// Strength fades to zero when approaching the maximum distance to the axis
+            fn strength(&self, position: &Vector) -> real {
+                let distance = flatten(&local_position(self, position), self.axis).length();
+                soft_cutoff(distance, self.max_distance, self.falloff_distance)
+            }
+
+            $( $( $extra_item )* )?
+        }
+    };
+}
+
+pub mod inner2d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis2D,
+            math::soft_cutoff,
+            util::{
+                in_any_group,
+                util2d::{flatten, global_center, global_direction, local_position},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area2D, IArea2D},
+        prelude::*,
+    };
+
+    /// Define a gravity pointing away from a line.
+    /// An axial gravity around the X axis points along the Y axis.
+    #[derive(GodotClass)]
+    #[class(base=Area2D)]
+    pub struct GravityAxial2D {
+        base: Base<Area2D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis2D,
+
+        /// Distance beyond which the field has no strength, zero means no limit
+        #[export(range = (0.0, 100.0, or_greater))]
+        max_distance: real,
+
+        /// Distance before the maximum over which the strength fades to zero
+        #[export(range = (0.0, 100.0, or_greater))]
+        falloff_distance: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityAxial2D => Vector2];
+
+    export_gravity_dict![GravityAxial2D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        max_distance,
+        falloff_distance,
+        inverted,
+    ]];
+
+    gravity_field_axial! {
+        GravityAxial2D where {
+            Area2D | IArea2D,
+            Vector2,
+            Axis2D
+        }
+    }
+}
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            math::{math3d::spin_velocity, soft_cutoff},
+            util::{
+                in_any_group,
+                util3d::{flatten, global_center, global_direction, local_position},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity pointing away from an axis.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityAxial3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis3D,
+
+        /// Distance beyond which the field has no strength, zero means no limit
+        #[export(range = (0.0, 100.0, or_greater))]
+        max_distance: real,
+
+        /// Distance before the maximum over which the strength fades to zero
+        #[export(range = (0.0, 100.0, or_greater))]
+        falloff_distance: real,
+
+        /// Angular speed in radians per second of the field around its axis,
+        /// used to compute the velocity inherited by the bodies.
+        #[export]
+        spin: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityAxial3D => Vector3];
+
+    export_gravity_dict![GravityAxial3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        max_distance,
+        falloff_distance,
+        spin,
+        inverted,
+    ]];
+
+    gravity_field_axial! {
+        GravityAxial3D where {
+            Area3D | IArea3D,
+            Vector3,
+            Axis3D
+        }
+        extend {
+            spin: 0.0,
+        } {
+            /// Velocity of a point spinning around the axis
+            fn angular_velocity(&self, position: &Vector3) -> Vector3 {
+                let local = local_position(self, position);
+                self.base().get_global_basis() * spin_velocity(&local, self.axis, self.spin)
+            }
+        }
+    }
+
+    #[godot_api(secondary)]
+    impl GravityAxial3D {
+        /// Get the velocity of a point at the given position spinning with the field
+        #[func]
+        pub fn get_angular_velocity(&self, position: Vector3) -> Vector3 {
+            self.angular_velocity(&position)
+        }
+    }
+}
+
+// re-export types
+pub use inner2d::GravityAxial2D;
+pub use inner3d::GravityAxial3D;
@@ -0,0 +1,249 @@
+//!
+//! Capsule gravity fields
+//!
+
+macro_rules! gravity_field_capsule {
+    (
+        $field_type:ty where {
+            $area:ty | $area_interface:ty,
+            $vector:ty,
+            $axis:ty
+        }
+    ) => {
+        // alias provided types
+        type Area = $area;
+        type Axis = $axis;
+        type Vector = $vector;
+
+        #[godot_api]
+        impl $area_interface for $field_type {
+            /// Instantiate the node
+            fn init(base: Base<Area>) -> Self {
+                Self {
+                    base,
+                    level: 0,
+                    affects_groups: Array::new(),
+                    channel: 0,
+                    axis: Axis::Y,
+                    height: 2.0,
+                    radius: 0.5,
+                    inverted: false,
+                }
+            }
+
+            /// Forward the body signals once the node enters the tree
+            fn ready(&mut self) {
+                self.connect_gravity_signals();
+            }
+        }
+
+        impl Field<Vector> for $field_type {
+            /// Get the priority level
+            #[inline]
+            fn level(&self) -> Level {
+                self.level
+            }
+
+            /// Up direction points away from the central segment of the capsule
+            fn local_up(&self, position: &Vector) -> Vector {
+                let up = capsule_up(position, self.axis, self.height, self.radius);
+
+                // Check if the direction should be inverted
+                if self.inverted { -up } else { up }
+            }
+
+            /// Up direction points away from the central segment of the capsule
+            fn global_up(&self, position: &Vector) -> Vector {
+                global_direction(self, position)
+            }
+
+            /// Center is the origin of the area
+            #[inline]
+            fn global_center(&self) -> Vector {
+                global_center(self)
+            }
+
+            /// Only affect the nodes in the selected groups
+            #[inline]
+            fn affects(&self, node: &Gd<Node>) -> bool {
+                in_any_group(&self.affects_groups, node)
+            }
+
+            /// Get the gravity channel
+            #[inline]
+            fn channel(&self) -> Channel {
+                self.channel
+            }
+        }
+    };
+}
+
+pub mod inner2d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis2D,
+            math::math2d::capsule_up,
+            util::{
+                in_any_group,
+                util2d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area2D, IArea2D},
+        prelude::*,
+    };
+
+    /// Define a gravity pulling toward a stadium, a rectangle with round ends.
+    #[derive(GodotClass)]
+    #[class(base=Area2D)]
+    pub struct GravityCapsule2D {
+        base: Base<Area2D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis2D,
+
+        /// Full height of the stadium along the axis, round ends included
+        #[export(range = (0.0, 10.0, or_greater))]
+        height: real,
+
+        /// Radius of the round ends
+        #[export(range = (0.0, 10.0, or_greater))]
+        radius: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityCapsule2D => Vector2];
+
+    export_gravity_dict![GravityCapsule2D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        height,
+        radius,
+        inverted,
+    ]];
+
+    gravity_field_capsule! {
+        GravityCapsule2D where {
+            Area2D | IArea2D,
+            Vector2,
+            Axis2D
+        }
+    }
+}
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            math::math3d::capsule_up,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity pulling toward a capsule, a cylinder with hemispherical caps.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityCapsule3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis3D,
+
+        /// Full height of the capsule along the axis, caps included
+        #[export(range = (0.0, 10.0, or_greater))]
+        height: real,
+
+        /// Radius of the capsule
+        #[export(range = (0.0, 10.0, or_greater))]
+        radius: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityCapsule3D => Vector3];
+
+    export_gravity_dict![GravityCapsule3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        height,
+        radius,
+        inverted,
+    ]];
+
+    gravity_field_capsule! {
+        GravityCapsule3D where {
+            Area3D | IArea3D,
+            Vector3,
+            Axis3D
+        }
+    }
+}
+
+// re-export types
+pub use inner2d::GravityCapsule2D;
+pub use inner3d::GravityCapsule3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::{axis::Axis3D, math::math3d::capsule_up};
+    use godot::builtin::{Transform3D, Vector3};
+
+    #[test]
+    fn capsule_follows_a_moved_field() {
+        // the global position lies left of the segment of a capsule moved to X = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(8.0, 0.5, 0.0);
+        assert_eq!(capsule_up(&local, Axis3D::Y, 4.0, 1.0), Vector3::LEFT);
+    }
+}
@@ -2,6 +2,118 @@
 //! Define center gravity fields
 //!
 
+use crate::gravity::math::soft_cutoff;
+use godot::prelude::{Export, GString, GodotConvert, Var, real};
+
+/// Select how the strength of a center gravity decreases with the distance
+#[repr(C)]
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[godot(via = GString)]
+pub enum Falloff {
+    /// Same strength at any distance
+    #[default]
+    Constant,
+
+    /// Strength decreases proportionally to the distance
+    Linear,
+
+    /// Strength decreases proportionally to the square of the distance
+    InverseSquare,
+}
+
+/// Parameters of a point gravity as set on an area of the physics server
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointGravity<V> {
+    /// Center of the gravity, passed through `GRAVITY_VECTOR`
+    pub center: V,
+
+    /// Distance at which the gravity equals its magnitude, zero for a constant magnitude
+    pub unit_distance: real,
+
+    /// Magnitude of the gravity, negative to push away from the center
+    pub gravity: real,
+}
+
+impl<V> PointGravity<V> {
+    /// Describe the point gravity matching a center field.
+    /// The physics server only knows constant and inverse square magnitudes,
+    /// a linear falloff is applied as constant.
+    pub fn new(
+        center: V,
+        falloff: Falloff,
+        reference_distance: real,
+        strength: real,
+        inverted: bool,
+    ) -> Self {
+        let unit_distance = match falloff {
+            Falloff::InverseSquare => reference_distance,
+            Falloff::Constant | Falloff::Linear => 0.0,
+        };
+        Self {
+            center,
+            unit_distance,
+            gravity: if inverted { -strength } else { strength },
+        }
+    }
+}
+
+/// Strength of a center gravity depending on the distance to its center
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Profile {
+    /// Strength of the gravity at the reference distance
+    pub strength: real,
+
+    /// How the strength decreases with the distance to the center
+    pub falloff: Falloff,
+
+    /// Distance from the center at which the strength is not attenuated
+    pub reference_distance: real,
+
+    /// Distance beyond which the field has no strength, zero means no limit
+    pub max_distance: real,
+
+    /// Distance before the maximum over which the strength fades to zero
+    pub falloff_distance: real,
+}
+
+impl Profile {
+    /// Profile of a planet, the strength follows an inverse square falloff
+    /// and equals the surface gravity at the radius of the planet.
+    /// It fades to zero through the atmosphere, a zero atmosphere height
+    /// leaves the strength unbounded.
+    pub fn planet(radius: real, surface_gravity: real, atmosphere_height: real) -> Self {
+        let atmosphere_height = atmosphere_height.max(0.0);
+        Self {
+            strength: surface_gravity,
+            falloff: Falloff::InverseSquare,
+            reference_distance: radius,
+            max_distance: if atmosphere_height > 0.0 {
+                radius + atmosphere_height
+            } else {
+                0.0
+            },
+            falloff_distance: atmosphere_height,
+        }
+    }
+
+    /// Strength decreases with the distance to the center according to the falloff,
+    /// it is equal to the strength at the reference distance.
+    /// It fades to zero when approaching the maximum distance.
+    pub fn strength_at(&self, distance: real) -> real {
+        // avoid an infinite strength right at the center
+        const EPSILON: real = 1e-4;
+        let distance = distance.max(EPSILON);
+        let ratio = self.reference_distance / distance;
+        self.strength
+            * soft_cutoff(distance, self.max_distance, self.falloff_distance)
+            * match self.falloff {
+                Falloff::Constant => 1.0,
+                Falloff::Linear => ratio,
+                Falloff::InverseSquare => ratio * ratio,
+            }
+    }
+}
+
 macro_rules! gravity_field_center {
     (
         $field_type:ty where {
@@ -20,9 +132,21 @@ macro_rules! gravity_field_center {
                 Self {
                     base,
                     level: 0,
+                    affects_groups: Array::new(),
+                    channel: 0,
+                    strength: 1.0,
+                    falloff: Falloff::Constant,
+                    reference_distance: 1.0,
+                    max_distance: 0.0,
+                    falloff_distance: 0.0,
                     inverted: false,
                 }
             }
+
+            /// Forward the body signals once the node enters the tree
+            fn ready(&mut self) {
+                self.connect_gravity_signals();
+            }
         }
 
         impl Field<Vector> for $field_type {
@@ -44,18 +168,123 @@ macro_rules! gravity_field_center {
             fn global_up(&self, position: &Vector) -> Vector {
                 global_direction(self, position)
             }
+
+            /// Center is the origin of the area
+            #[inline]
+            fn global_center(&self) -> Vector {
+                global_center(self)
+            }
+
+            /// Only affect the nodes in the selected groups
+            #[inline]
+            fn affects(&self, node: &Gd<Node>) -> bool {
+                in_any_group(&self.affects_groups, node)
+            }
+
+            /// Get the gravity channel
+            #[inline]
+            fn channel(&self) -> Channel {
+                self.channel
+            }
+
+            /// Strength decreases with the distance to the center according to the falloff,
+            /// it is equal to the exported strength at the reference distance.
+            /// It fades to zero when approaching the maximum distance.
+            fn strength(&self, position: &Vector) -> real {
+                self.profile()
+                    .strength_at(position.distance_to(self.global_center()))
+            }
+        }
+
+        #[godot_api(secondary)]
+        impl $field_type {
+            /// Apply the gravity of this field to an area of the physics server.
+            /// Set the `GRAVITY_OVERRIDE_MODE` to replace, `GRAVITY_IS_POINT` to true,
+            /// the `GRAVITY_VECTOR` to the global center of this field, which Godot uses
+            /// as the center of a point gravity, and `GRAVITY_POINT_UNIT_DISTANCE` to the
+            /// reference distance for an inverse square falloff or to zero for a constant
+            /// magnitude, a linear falloff cannot be represented by the physics server
+            /// and is applied as constant. `GRAVITY` is set to the strength of this field,
+            /// negated if the field is inverted.
+            /// The center is expressed in the space of the target area,
+            /// it is expected to have an identity transform.
+            #[func]
+            pub fn apply_to_area(&self, area: Rid) {
+                let point = self.point_gravity();
+                let mut server = PhysicsServer::singleton();
+                server.area_set_param(
+                    area,
+                    AreaParameter::GRAVITY_OVERRIDE_MODE,
+                    &AreaSpaceOverrideMode::REPLACE.to_variant(),
+                );
+                server.area_set_param(area, AreaParameter::GRAVITY_IS_POINT, &true.to_variant());
+                server.area_set_param(
+                    area,
+                    AreaParameter::GRAVITY_VECTOR,
+                    &point.center.to_variant(),
+                );
+                server.area_set_param(
+                    area,
+                    AreaParameter::GRAVITY_POINT_UNIT_DISTANCE,
+                    &point.unit_distance.to_variant(),
+                );
+                server.area_set_param(area, AreaParameter::GRAVITY, &point.gravity.to_variant());
+            }
+        }
+
+        impl $field_type {
+            /// Strength profile of this field
+            pub fn profile(&self) -> Profile {
+                Profile {
+                    strength: self.strength,
+                    falloff: self.falloff,
+                    reference_distance: self.reference_distance,
+                    max_distance: self.max_distance,
+                    falloff_distance: self.falloff_distance,
+                }
+            }
+
+            /// Set the strength profile of this field
+            pub fn set_profile(&mut self, profile: Profile) {
+                self.strength = profile.strength;
+                self.falloff = profile.falloff;
+                self.reference_distance = profile.reference_distance;
+                self.max_distance = profile.max_distance;
+                self.falloff_distance = profile.falloff_distance;
+            }
+
+            /// Point gravity of the physics server matching this field
+            pub fn point_gravity(&self) -> PointGravity<Vector> {
+                PointGravity::new(
+                    self.global_center(),
+                    self.falloff,
+                    self.reference_distance,
+                    self.strength,
+                    self.inverted,
+                )
+            }
         }
     };
 }
 
 pub mod inner2d {
 
+    use super::{Falloff, PointGravity, Profile};
     use crate::{
-        export_gravity_up,
-        gravity::{Field, Level, util::util2d::global_direction},
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            util::{
+                in_any_group,
+                util2d::{global_center, global_direction},
+            },
+        },
     };
     use godot::{
-        classes::{Area2D, IArea2D},
+        classes::{
+            Area2D, IArea2D, PhysicsServer2D as PhysicsServer,
+            physics_server_2d::{AreaParameter, AreaSpaceOverrideMode},
+        },
         prelude::*,
     };
 
@@ -69,6 +298,35 @@ pub mod inner2d {
         #[export]
         level: Level,
 
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Strength of the gravity at the reference distance
+        #[export(range = (0.0, 10.0, or_greater))]
+        strength: real,
+
+        /// How the strength decreases with the distance to the center
+        #[export]
+        falloff: Falloff,
+
+        /// Distance from the center at which the strength is not attenuated
+        #[export(range = (0.001, 10.0, or_greater))]
+        reference_distance: real,
+
+        /// Distance beyond which the field has no strength, zero means no limit
+        #[export(range = (0.0, 100.0, or_greater))]
+        max_distance: real,
+
+        /// Distance before the maximum over which the strength fades to zero
+        #[export(range = (0.0, 100.0, or_greater))]
+        falloff_distance: real,
+
         /// Inverse the gravity
         #[export]
         inverted: bool,
@@ -76,6 +334,18 @@ pub mod inner2d {
 
     export_gravity_up![GravityCenter2D => Vector2];
 
+    export_gravity_dict![GravityCenter2D => [
+        level,
+        affects_groups,
+        channel,
+        strength,
+        falloff,
+        reference_distance,
+        max_distance,
+        falloff_distance,
+        inverted,
+    ]];
+
     gravity_field_center! {
         GravityCenter2D where {
             Area2D | IArea2D,
@@ -86,18 +356,29 @@ pub mod inner2d {
 
 pub mod inner3d {
 
+    use super::{Falloff, PointGravity, Profile};
     use crate::{
-        export_gravity_up,
-        gravity::{Field, Level, util::util3d::global_direction},
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
     };
     use godot::{
-        classes::{Area3D, IArea3D},
+        classes::{
+            Area3D, IArea3D, PhysicsServer3D as PhysicsServer,
+            physics_server_3d::{AreaParameter, AreaSpaceOverrideMode},
+        },
         prelude::*,
     };
 
     /// Define a gravity centered around a point.
     #[derive(GodotClass)]
-    #[class(base=Area3D)]
+    #[cfg_attr(feature = "editor", class(tool, base=Area3D))]
+    #[cfg_attr(not(feature = "editor"), class(base=Area3D))]
     pub struct GravityCenter3D {
         base: Base<Area3D>,
 
@@ -105,6 +386,35 @@ pub mod inner3d {
         #[export]
         level: Level,
 
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Strength of the gravity at the reference distance
+        #[export(range = (0.0, 10.0, or_greater))]
+        strength: real,
+
+        /// How the strength decreases with the distance to the center
+        #[export]
+        falloff: Falloff,
+
+        /// Distance from the center at which the strength is not attenuated
+        #[export(range = (0.001, 10.0, or_greater))]
+        reference_distance: real,
+
+        /// Distance beyond which the field has no strength, zero means no limit
+        #[export(range = (0.0, 100.0, or_greater))]
+        max_distance: real,
+
+        /// Distance before the maximum over which the strength fades to zero
+        #[export(range = (0.0, 100.0, or_greater))]
+        falloff_distance: real,
+
         /// Inverse the gravity
         #[export]
         inverted: bool,
@@ -112,14 +422,119 @@ pub mod inner3d {
 
     export_gravity_up![GravityCenter3D => Vector3];
 
+    export_gravity_dict![GravityCenter3D => [
+        level,
+        affects_groups,
+        channel,
+        strength,
+        falloff,
+        reference_distance,
+        max_distance,
+        falloff_distance,
+        inverted,
+    ]];
+
     gravity_field_center! {
         GravityCenter3D where {
             Area3D | IArea3D,
             Vector3
         }
     }
+
+    #[godot_api(secondary)]
+    impl GravityCenter3D {
+        /// Configure this field as the gravity of a planet in a single call.
+        /// The falloff is set to inverse square with the reference distance at the radius,
+        /// so the strength equals the surface gravity on the surface of the planet.
+        /// The maximum distance is set to the top of the atmosphere and the strength
+        /// fades to zero through the atmosphere, a zero atmosphere height leaves the
+        /// strength unbounded. The center field does not build colliders, the shape
+        /// of the area and the solid ground of the planet are left to the scene.
+        #[func]
+        pub fn setup_planet(
+            &mut self,
+            radius: real,
+            surface_gravity: real,
+            atmosphere_height: real,
+        ) {
+            self.set_profile(Profile::planet(radius, surface_gravity, atmosphere_height));
+        }
+    }
 }
 
 // re-export types
 pub use inner2d::GravityCenter2D;
 pub use inner3d::GravityCenter3D;
+
+#[cfg(test)]
+mod tests {
+    use super::{Falloff, PointGravity, Profile};
+    use godot::builtin::{Transform3D, Vector3, math::ApproxEq};
+
+    #[test]
+    fn point_gravity_uses_field_strength() {
+        let center = Vector3::new(1.0, 2.0, 3.0);
+        let point = PointGravity::new(center, Falloff::InverseSquare, 4.0, 9.8, false);
+        assert_eq!(point.center, center);
+        assert_eq!(point.unit_distance, 4.0);
+        assert_eq!(point.gravity, 9.8);
+    }
+
+    #[test]
+    fn point_gravity_constant_for_other_falloffs() {
+        for falloff in [Falloff::Constant, Falloff::Linear] {
+            let point = PointGravity::new(Vector3::ZERO, falloff, 4.0, 2.0, false);
+            assert_eq!(point.unit_distance, 0.0);
+        }
+    }
+
+    #[test]
+    fn point_gravity_inverted_pushes_away() {
+        let point = PointGravity::new(Vector3::ZERO, Falloff::Constant, 1.0, 2.0, true);
+        assert_eq!(point.gravity, -2.0);
+    }
+
+    #[test]
+    fn planet_pulls_with_the_surface_gravity_on_its_surface() {
+        let planet = Profile::planet(10.0, 9.8, 5.0);
+        let surface = Vector3::new(0.0, 0.0, 10.0);
+        assert!(planet.strength_at(surface.length()).approx_eq(&9.8));
+
+        // the physics server pulls toward the center with the same magnitude
+        let point = PointGravity::new(
+            Vector3::ZERO,
+            planet.falloff,
+            planet.reference_distance,
+            planet.strength,
+            false,
+        );
+        let ratio = point.unit_distance / surface.length();
+        let gravity = (point.center - surface).normalized() * point.gravity * ratio * ratio;
+        assert!(gravity.approx_eq(&Vector3::new(0.0, 0.0, -9.8)));
+    }
+
+    #[test]
+    fn moved_planet_pulls_toward_its_own_center() {
+        // the direction and the falloff are both measured from a planet moved to X = 100
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(100.0, 0.0, 0.0));
+        let surface = Vector3::new(90.0, 0.0, 0.0);
+        let local = trs.affine_inverse() * surface;
+        assert_eq!(local.normalized_or_zero(), Vector3::LEFT);
+
+        let planet = Profile::planet(10.0, 9.8, 5.0);
+        let distance = surface.distance_to(trs.origin);
+        assert!(planet.strength_at(distance).approx_eq(&9.8));
+    }
+
+    #[test]
+    fn planet_gravity_fades_through_the_atmosphere() {
+        let planet = Profile::planet(10.0, 9.8, 5.0);
+        assert!(planet.strength_at(12.5) < planet.strength_at(11.0));
+        assert_eq!(planet.strength_at(15.0), 0.0);
+
+        // without atmosphere the strength is unbounded
+        let bare = Profile::planet(10.0, 9.8, 0.0);
+        assert!(bare.strength_at(10.0).approx_eq(&9.8));
+        assert!(bare.strength_at(100.0) > 0.0);
+    }
+}
@@ -0,0 +1,226 @@
+//!
+//! Gravity field combining other fields
+//!
+
+use godot::prelude::{Export, GString, GodotConvert, Var};
+
+/// Select how the directions of the combined fields are merged
+#[repr(C)]
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[godot(via = GString)]
+pub enum CompositeMode {
+    /// Sum the gravity of every field
+    #[default]
+    Add,
+
+    /// Only use the strongest gravity
+    Max,
+
+    /// Average the gravity of every field
+    Average,
+}
+
+pub mod inner3d {
+
+    use super::CompositeMode;
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            query::DynGravityField3D,
+            util::{in_any_group, util3d::global_center},
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+    use std::cell::RefCell;
+
+    thread_local! {
+        /// Composites currently being evaluated on this thread
+        static EVALUATING: RefCell<Vec<InstanceId>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Mark a composite as being evaluated until dropped. A composite listing
+    /// itself, directly or through a cycle of composites, would otherwise
+    /// recurse endlessly, the fields being evaluated are skipped instead.
+    pub(super) struct Evaluating(InstanceId);
+
+    impl Evaluating {
+        /// Start evaluating the composite
+        pub(super) fn enter(id: InstanceId) -> Self {
+            EVALUATING.with_borrow_mut(|ids| ids.push(id));
+            Self(id)
+        }
+
+        /// Check if the field is a composite being evaluated
+        pub(super) fn contains(id: InstanceId) -> bool {
+            EVALUATING.with_borrow(|ids| ids.contains(&id))
+        }
+    }
+
+    impl Drop for Evaluating {
+        fn drop(&mut self) {
+            EVALUATING.with_borrow_mut(|ids| {
+                if let Some(index) = ids.iter().rposition(|id| *id == self.0) {
+                    ids.remove(index);
+                }
+            });
+        }
+    }
+
+    /// Define a gravity combining the gravity of other fields deterministically.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityComposite3D {
+        base: Base<Area3D>,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Gravity fields to combine, they define the priority level.
+        /// The composite itself and the composites combining it are skipped.
+        #[export]
+        fields: Array<DynGravityField3D>,
+
+        /// How the gravity of the fields is combined
+        #[export]
+        mode: CompositeMode,
+    }
+
+    export_gravity_up![GravityComposite3D => Vector3];
+
+    export_gravity_dict![GravityComposite3D => [affects_groups, channel, fields, mode]];
+
+    #[godot_api]
+    impl IArea3D for GravityComposite3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                affects_groups: Array::new(),
+                channel: 0,
+                fields: Array::new(),
+                mode: CompositeMode::Add,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    impl GravityComposite3D {
+        /// Evaluate the combined fields, skipping the composites being evaluated
+        fn each_field<T>(&self, eval: impl Fn(&dyn Field<Vector3>) -> T) -> Vec<T> {
+            let _evaluating = Evaluating::enter(self.base().instance_id());
+            self.fields
+                .iter_shared()
+                .filter(|field| !Evaluating::contains(field.instance_id()))
+                .map(|field| eval(&*field.dyn_bind()))
+                .collect()
+        }
+
+        /// Combine the gravity of the fields, scaled by their strength
+        fn combined(&self, position: &Vector3) -> Vector3 {
+            let gravities =
+                self.each_field(|field| field.global_up(position) * field.strength(position));
+
+            match self.mode {
+                CompositeMode::Add => gravities
+                    .iter()
+                    .fold(Vector3::ZERO, |sum, gravity| sum + *gravity),
+                CompositeMode::Max => gravities
+                    .into_iter()
+                    .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+                    .unwrap_or(Vector3::ZERO),
+                CompositeMode::Average => {
+                    let sum = gravities
+                        .iter()
+                        .fold(Vector3::ZERO, |sum, gravity| sum + *gravity);
+                    sum / gravities.len().max(1) as real
+                }
+            }
+        }
+    }
+
+    impl Field<Vector3> for GravityComposite3D {
+        /// Highest priority level of the combined fields
+        #[inline]
+        fn level(&self) -> Level {
+            self.each_field(|field| field.level())
+                .into_iter()
+                .max()
+                .unwrap_or(Level::MIN)
+        }
+
+        /// Up direction in the local space of this node
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            self.base().get_global_basis().inverse() * self.global_up(position)
+        }
+
+        /// Up direction of the combined gravity
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            self.combined(position).normalized_or_zero()
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+
+        /// Strength of the combined gravity
+        #[inline]
+        fn strength(&self, position: &Vector3) -> real {
+            self.combined(position).length()
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityComposite3D;
+
+#[cfg(test)]
+mod tests {
+    use super::inner3d::Evaluating;
+    use godot::obj::InstanceId;
+
+    #[test]
+    fn composites_being_evaluated_are_skipped() {
+        let (outer, inner) = (InstanceId::from_i64(1), InstanceId::from_i64(2));
+        {
+            let _outer = Evaluating::enter(outer);
+            assert!(Evaluating::contains(outer));
+            assert!(!Evaluating::contains(inner));
+            {
+                // a cycle leads back to the outer composite
+                let _inner = Evaluating::enter(inner);
+                assert!(Evaluating::contains(outer));
+                assert!(Evaluating::contains(inner));
+            }
+            assert!(!Evaluating::contains(inner));
+        }
+        assert!(!Evaluating::contains(outer));
+    }
+}
@@ -0,0 +1,284 @@
+//!
+//! Conic gravity fields
+//!
+
+macro_rules! gravity_field_conic {
+    (
+        $field_type:ty where {
+            $area:ty | $area_interface:ty,
+            $vector:ty,
+            $axis:ty
+        }
+    ) => {
+        // alias provided types
+        type Area = $area;
+        type Axis = $axis;
+        type Vector = $vector;
+
+        #[godot_api]
+        impl $area_interface for $field_type {
+            /// Instantiate the node
+            fn init(base: Base<Area>) -> Self {
+                Self {
+                    base,
+                    level: 0,
+                    affects_groups: Array::new(),
+                    channel: 0,
+                    axis: Axis::Y,
+                    height: 1.0,
+                    radius: 1.0,
+                    smoothing: 0.0,
+                    inverted: false,
+                }
+            }
+
+            /// Forward the body signals once the node enters the tree
+            fn ready(&mut self) {
+                self.connect_gravity_signals();
+            }
+        }
+
+        impl Field<Vector> for $field_type {
+            /// Get the priority level
+            #[inline]
+            fn level(&self) -> Level {
+                self.level
+            }
+
+            /// Up direction is normal to the side of the cone,
+            /// between pointing away from the axis and along the axis.
+            fn local_up(&self, position: &Vector) -> Vector {
+                let up = cone_up(
+                    position,
+                    self.axis,
+                    self.height,
+                    self.radius,
+                    self.smoothing,
+                );
+
+                // Check if the direction should be inverted
+                if self.inverted { -up } else { up }
+            }
+
+            /// Up direction is normal to the side of the cone
+            fn global_up(&self, position: &Vector) -> Vector {
+                global_direction(self, position)
+            }
+
+            /// Center is the origin of the area
+            #[inline]
+            fn global_center(&self) -> Vector {
+                global_center(self)
+            }
+
+            /// Only affect the nodes in the selected groups
+            #[inline]
+            fn affects(&self, node: &Gd<Node>) -> bool {
+                in_any_group(&self.affects_groups, node)
+            }
+
+            /// Get the gravity channel
+            #[inline]
+            fn channel(&self) -> Channel {
+                self.channel
+            }
+
+            /// Bounds enclose the cone standing on the origin along the axis
+            fn bounds(&self) -> Option<<Vector as WithBounds>::Bounds> {
+                let axial = self.axis.to_vector();
+                let along = axial.abs();
+                let half_extents =
+                    (Vector::ONE - along) * self.radius + along * (self.height * 0.5);
+                Some(global_bounds(
+                    self,
+                    axial * (self.height * 0.5),
+                    half_extents,
+                ))
+            }
+        }
+    };
+}
+
+pub mod inner2d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level, WithBounds,
+            axis::Axis2D,
+            math::math2d::cone_up,
+            util::{
+                in_any_group,
+                util2d::{global_bounds, global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area2D, IArea2D},
+        prelude::*,
+    };
+
+    /// Define a wedge shaped gravity tilted between the axis and the radial direction.
+    #[derive(GodotClass)]
+    #[class(base=Area2D)]
+    pub struct GravityConic2D {
+        base: Base<Area2D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis2D,
+
+        /// Height of the cone along the axis
+        #[export(range = (0.0, 10.0, or_greater))]
+        height: real,
+
+        /// Radius of the base of the cone
+        #[export(range = (0.0, 10.0, or_greater))]
+        radius: real,
+
+        /// Smooth the transition of the gravity crossing the side of the cone,
+        /// zero keeps the direction normal to the side everywhere.
+        #[export(range = (0.0, 1.0))]
+        smoothing: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityConic2D => Vector2];
+
+    export_gravity_dict![GravityConic2D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        height,
+        radius,
+        smoothing,
+        inverted,
+    ]];
+
+    gravity_field_conic! {
+        GravityConic2D where {
+            Area2D | IArea2D,
+            Vector2,
+            Axis2D
+        }
+    }
+}
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level, WithBounds,
+            axis::Axis3D,
+            math::math3d::cone_up,
+            util::{
+                in_any_group,
+                util3d::{global_bounds, global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity normal to the side of a cone around an axis.
+    #[derive(GodotClass)]
+    #[cfg_attr(feature = "editor", class(tool, base=Area3D))]
+    #[cfg_attr(not(feature = "editor"), class(base=Area3D))]
+    pub struct GravityConic3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis3D,
+
+        /// Height of the cone along the axis
+        #[export(range = (0.0, 10.0, or_greater))]
+        height: real,
+
+        /// Radius of the base of the cone
+        #[export(range = (0.0, 10.0, or_greater))]
+        radius: real,
+
+        /// Smooth the transition of the gravity crossing the side of the cone,
+        /// zero keeps the direction normal to the side everywhere.
+        #[export(range = (0.0, 1.0))]
+        smoothing: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityConic3D => Vector3];
+
+    export_gravity_dict![GravityConic3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        height,
+        radius,
+        smoothing,
+        inverted,
+    ]];
+
+    gravity_field_conic! {
+        GravityConic3D where {
+            Area3D | IArea3D,
+            Vector3,
+            Axis3D
+        }
+    }
+}
+
+// re-export types
+pub use inner2d::GravityConic2D;
+pub use inner3d::GravityConic3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::{axis::Axis3D, math::math3d::cone_up};
+    use godot::builtin::{Transform3D, Vector3, math::ApproxEq};
+
+    #[test]
+    fn cone_follows_a_moved_field() {
+        // the global position lies left of the axis of a cone moved to X = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(8.0, 0.0, 0.0);
+        let up = cone_up(&local, Axis3D::Y, 1.0, 1.0, 0.0);
+        assert!(up.approx_eq(&Vector3::new(-1.0, 1.0, 0.0).normalized()));
+    }
+}
@@ -0,0 +1,173 @@
+//!
+//! Cylindrical gravity field
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            math::math3d::{cylinder_up, spin_velocity},
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction, local_position},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity pulling toward a finite cylinder, toward its axis
+    /// between its caps and toward its caps beyond its ends.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityCylinder3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis3D,
+
+        /// Radius of the cylinder
+        #[export(range = (0.0, 10.0, or_greater))]
+        radius: real,
+
+        /// Height of the cylinder along the axis, centered on the origin
+        #[export(range = (0.0, 10.0, or_greater))]
+        height: real,
+
+        /// Angular speed in radians per second of the cylinder around its axis,
+        /// used to compute the velocity inherited by the bodies.
+        #[export]
+        spin: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityCylinder3D => Vector3];
+
+    export_gravity_dict![GravityCylinder3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        radius,
+        height,
+        spin,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityCylinder3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                axis: Axis3D::Y,
+                radius: 1.0,
+                height: 2.0,
+                spin: 0.0,
+                inverted: false,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    #[godot_api(secondary)]
+    impl GravityCylinder3D {
+        /// Get the velocity of a point at the given position spinning with the cylinder
+        #[func]
+        pub fn get_angular_velocity(&self, position: Vector3) -> Vector3 {
+            self.angular_velocity(&position)
+        }
+    }
+
+    impl Field<Vector3> for GravityCylinder3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction points away from the axis between the caps
+        /// and away from the caps beyond the ends of the cylinder.
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = cylinder_up(position, self.axis, self.radius, self.height);
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction points away from the cylinder
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+
+        /// Velocity of a point spinning around the axis of the cylinder
+        fn angular_velocity(&self, position: &Vector3) -> Vector3 {
+            let local = local_position(self, position);
+            self.base().get_global_basis() * spin_velocity(&local, self.axis, self.spin)
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityCylinder3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::{axis::Axis3D, math::math3d::cylinder_up};
+    use godot::builtin::{Transform3D, Vector3};
+
+    #[test]
+    fn cylinder_follows_a_moved_field() {
+        // the global position lies left of a cylinder moved to X = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(9.0, 0.0, 0.0);
+        assert_eq!(cylinder_up(&local, Axis3D::Y, 0.5, 2.0), Vector3::LEFT);
+    }
+}
@@ -0,0 +1,219 @@
+//!
+//! Directional gravity fields
+//!
+
+macro_rules! gravity_field_directional {
+    (
+        $field_type:ty where {
+            $area:ty | $area_interface:ty,
+            $vector:ty
+        }
+    ) => {
+        // alias provided types
+        type Area = $area;
+        type Vector = $vector;
+
+        #[godot_api]
+        impl $area_interface for $field_type {
+            /// Instantiate the node
+            fn init(base: Base<Area>) -> Self {
+                Self {
+                    base,
+                    level: 0,
+                    affects_groups: Array::new(),
+                    channel: 0,
+                    direction: Vector::UP,
+                    inverted: false,
+                }
+            }
+
+            /// Forward the body signals once the node enters the tree
+            fn ready(&mut self) {
+                self.connect_gravity_signals();
+            }
+        }
+
+        impl Field<Vector> for $field_type {
+            /// Get the priority level
+            #[inline]
+            fn level(&self) -> Level {
+                self.level
+            }
+
+            /// Up direction is solely defined by the direction selected
+            fn local_up(&self, _position: &Vector) -> Vector {
+                let up = self.direction;
+
+                // Check if the direction should be inverted
+                if self.inverted { -up } else { up }
+            }
+
+            /// Up direction is solely defined by the direction selected
+            fn global_up(&self, position: &Vector) -> Vector {
+                global_direction(self, position)
+            }
+
+            /// Center is the origin of the area
+            #[inline]
+            fn global_center(&self) -> Vector {
+                global_center(self)
+            }
+
+            /// Only affect the nodes in the selected groups
+            #[inline]
+            fn affects(&self, node: &Gd<Node>) -> bool {
+                in_any_group(&self.affects_groups, node)
+            }
+
+            /// Get the gravity channel
+            #[inline]
+            fn channel(&self) -> Channel {
+                self.channel
+            }
+        }
+
+        #[godot_api(secondary)]
+        impl $field_type {
+            /// Set the direction of the gravity, the vector is normalized.
+            /// A zero vector is ignored and the previous direction is kept.
+            #[func]
+            pub fn set_direction(&mut self, direction: Vector) {
+                if !direction.is_zero_approx() {
+                    self.direction = direction.normalized();
+                }
+            }
+        }
+    };
+}
+
+pub mod inner2d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            util::{
+                in_any_group,
+                util2d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area2D, IArea2D},
+        prelude::*,
+    };
+
+    /// Define a gravity along an arbitrary direction.
+    #[derive(GodotClass)]
+    #[class(base=Area2D)]
+    pub struct GravityDirectional2D {
+        base: Base<Area2D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// UP direction of the gravity, normalized
+        #[export]
+        #[var(get, set = set_direction)]
+        direction: Vector2,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityDirectional2D => Vector2];
+
+    export_gravity_dict![GravityDirectional2D => [
+        level,
+        affects_groups,
+        channel,
+        direction,
+        inverted,
+    ]];
+
+    gravity_field_directional! {
+        GravityDirectional2D where {
+            Area2D | IArea2D,
+            Vector2
+        }
+    }
+}
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity along an arbitrary direction.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityDirectional3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// UP direction of the gravity, normalized
+        #[export]
+        #[var(get, set = set_direction)]
+        direction: Vector3,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityDirectional3D => Vector3];
+
+    export_gravity_dict![GravityDirectional3D => [
+        level,
+        affects_groups,
+        channel,
+        direction,
+        inverted,
+    ]];
+
+    gravity_field_directional! {
+        GravityDirectional3D where {
+            Area3D | IArea3D,
+            Vector3
+        }
+    }
+}
+
+// re-export types
+pub use inner2d::GravityDirectional2D;
+pub use inner3d::GravityDirectional3D;
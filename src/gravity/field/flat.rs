@@ -22,8 +22,32 @@ macro_rules! gravity_field_flat {
                 Self {
                     base,
                     level: 0,
+                    affects_groups: Array::new(),
+                    channel: 0,
                     axis: Axis::Y,
+                    strength: 1.0,
                     inverted: false,
+                    one_sided: false,
+                    plane_offset: 0.0,
+                    animate: false,
+                    angular_velocity: Default::default(),
+                    rotation: IDENTITY,
+                }
+            }
+
+            /// Forward the body signals once the node enters the tree
+            fn ready(&mut self) {
+                self.connect_gravity_signals();
+            }
+
+            /// Rotate the axis while animated
+            fn physics_process(&mut self, delta: f64) {
+                // do not animate the field while editing the scene
+                if self.animate && !godot::classes::Engine::singleton().is_editor_hint() {
+                    self.rotation = advance(self.rotation, self.angular_velocity, delta as real);
+                    let up = self.global_up(&self.global_center());
+                    self.base_mut()
+                        .emit_signal("direction_changed", &[up.to_variant()]);
                 }
             }
         }
@@ -35,19 +59,80 @@ macro_rules! gravity_field_flat {
                 self.level
             }
 
-            /// Up direction is solely defined by the axis selected
-            fn local_up(&self, _position: &Vector) -> Vector {
+            /// Up direction is defined by the axis selected and its animation
+            fn local_up(&self, position: &Vector) -> Vector {
                 // Pick the up direction based on the axis selected
-                let up = self.axis.to_vector();
+                let up = rotate(self.axis.to_vector(), self.rotation);
 
                 // Check if the direction should be inverted
-                if self.inverted { -up } else { up }
+                let up = if self.inverted { -up } else { up };
+                flat_up(up, self.one_sided, self.plane_offset, position)
             }
 
-            /// Up direction is solely defined by the axis selected
+            /// Up direction is defined by the axis selected, the position is brought
+            /// into the local space of the field to find the side of its plane.
             fn global_up(&self, position: &Vector) -> Vector {
                 global_direction(self, position)
             }
+
+            /// Center is the origin of the area
+            #[inline]
+            fn global_center(&self) -> Vector {
+                global_center(self)
+            }
+
+            /// Only affect the nodes in the selected groups
+            #[inline]
+            fn affects(&self, node: &Gd<Node>) -> bool {
+                in_any_group(&self.affects_groups, node)
+            }
+
+            /// Get the gravity channel
+            #[inline]
+            fn channel(&self) -> Channel {
+                self.channel
+            }
+
+            /// Strength is constant over the whole field
+            #[inline]
+            fn strength(&self, _position: &Vector) -> real {
+                self.strength
+            }
+        }
+
+        /// UP direction for a position in the local space of the field,
+        /// zero behind the plane of a one sided field.
+        #[inline]
+        pub(super) fn flat_up(
+            up: Vector,
+            one_sided: bool,
+            plane_offset: real,
+            position: &Vector,
+        ) -> Vector {
+            if one_sided && position.dot(up) < plane_offset {
+                Vector::ZERO
+            } else {
+                up
+            }
+        }
+
+        #[godot_api(secondary)]
+        impl $field_type {
+            /// Apply the gravity direction of this field to an area of the physics server.
+            /// Set the `GRAVITY_OVERRIDE_MODE` to replace, `GRAVITY_IS_POINT` to false and
+            /// the `GRAVITY_VECTOR` to the global DOWN direction of this field.
+            #[func]
+            pub fn apply_to_area(&self, area: Rid) {
+                let mut server = PhysicsServer::singleton();
+                let down = -self.global_up(&self.global_center());
+                server.area_set_param(
+                    area,
+                    AreaParameter::GRAVITY_OVERRIDE_MODE,
+                    &AreaSpaceOverrideMode::REPLACE.to_variant(),
+                );
+                server.area_set_param(area, AreaParameter::GRAVITY_IS_POINT, &false.to_variant());
+                server.area_set_param(area, AreaParameter::GRAVITY_VECTOR, &down.to_variant());
+            }
         }
     };
 }
@@ -55,11 +140,22 @@ macro_rules! gravity_field_flat {
 pub mod inner2d {
 
     use crate::{
-        export_gravity_up,
-        gravity::{Field, Level, axis::Axis2D, util::util2d::global_direction},
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis2D,
+            util::{
+                in_any_group,
+                util2d::{global_center, global_direction},
+            },
+        },
     };
     use godot::{
-        classes::{Area2D, IArea2D},
+        builtin::real_consts,
+        classes::{
+            Area2D, IArea2D, PhysicsServer2D as PhysicsServer,
+            physics_server_2d::{AreaParameter, AreaSpaceOverrideMode},
+        },
         prelude::*,
     };
 
@@ -73,16 +169,81 @@ pub mod inner2d {
         #[export]
         level: Level,
 
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
         /// Central Axis
         #[export]
         axis: Axis2D,
 
+        /// Strength of the gravity
+        #[export(range = (0.0, 10.0, or_greater))]
+        strength: real,
+
         /// Inverse the gravity
         #[export]
         inverted: bool,
+
+        /// Only apply the gravity in front of the plane
+        /// perpendicular to the UP direction.
+        #[export]
+        one_sided: bool,
+
+        /// Offset of the plane along the UP direction
+        #[export]
+        plane_offset: real,
+
+        /// Rotate the axis over time
+        #[export]
+        animate: bool,
+
+        /// Rotation speed of the axis in radians per second
+        #[export]
+        angular_velocity: real,
+
+        /// Current rotation of the axis, kept when the animation stops
+        rotation: real,
     }
 
-    export_gravity_up![GravityFlat2D => Vector2];
+    /// Rotation of the axis at rest
+    const IDENTITY: real = 0.0;
+
+    /// Advance the rotation of the axis
+    #[inline]
+    fn advance(rotation: real, angular_velocity: real, delta: real) -> real {
+        (rotation + angular_velocity * delta) % real_consts::TAU
+    }
+
+    /// Apply the rotation to the axis
+    #[inline]
+    fn rotate(up: Vector2, rotation: real) -> Vector2 {
+        up.rotated(rotation)
+    }
+
+    export_gravity_up![GravityFlat2D => Vector2;
+        /// Emitted every physics frame while the axis is animated
+        #[signal]
+        fn direction_changed(up: Vector2);
+    ];
+
+    export_gravity_dict![GravityFlat2D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        strength,
+        inverted,
+        one_sided,
+        plane_offset,
+        animate,
+        angular_velocity,
+    ]];
 
     gravity_field_flat! {
         GravityFlat2D where {
@@ -96,17 +257,28 @@ pub mod inner2d {
 pub mod inner3d {
 
     use crate::{
-        export_gravity_up,
-        gravity::{Field, Level, axis::Axis3D, util::util3d::global_direction},
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
     };
     use godot::{
-        classes::{Area3D, IArea3D},
+        classes::{
+            Area3D, IArea3D, PhysicsServer3D as PhysicsServer,
+            physics_server_3d::{AreaParameter, AreaSpaceOverrideMode},
+        },
         prelude::*,
     };
 
     /// Define a gravity based on an axis direction.
     #[derive(GodotClass)]
-    #[class(base=Area3D)]
+    #[cfg_attr(feature = "editor", class(tool, base=Area3D))]
+    #[cfg_attr(not(feature = "editor"), class(base=Area3D))]
     pub struct GravityFlat3D {
         base: Base<Area3D>,
 
@@ -114,16 +286,90 @@ pub mod inner3d {
         #[export]
         level: Level,
 
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
         /// Central Axis
         #[export]
         axis: Axis3D,
 
+        /// Strength of the gravity
+        #[export(range = (0.0, 10.0, or_greater))]
+        strength: real,
+
         /// Inverse the gravity
         #[export]
         inverted: bool,
+
+        /// Only apply the gravity in front of the plane
+        /// perpendicular to the UP direction.
+        #[export]
+        one_sided: bool,
+
+        /// Offset of the plane along the UP direction
+        #[export]
+        plane_offset: real,
+
+        /// Rotate the axis over time
+        #[export]
+        animate: bool,
+
+        /// Rotation speed of the axis, its direction is the rotation axis
+        /// and its length the speed in radians per second
+        #[export]
+        angular_velocity: Vector3,
+
+        /// Current rotation of the axis, kept when the animation stops
+        rotation: Quaternion,
     }
 
-    export_gravity_up![GravityFlat3D => Vector3];
+    /// Rotation of the axis at rest
+    const IDENTITY: Quaternion = Quaternion::IDENTITY;
+
+    /// Advance the rotation of the axis
+    #[inline]
+    fn advance(rotation: Quaternion, angular_velocity: Vector3, delta: real) -> Quaternion {
+        if angular_velocity.is_zero_approx() {
+            rotation
+        } else {
+            let step = Quaternion::from_axis_angle(
+                angular_velocity.normalized(),
+                angular_velocity.length() * delta,
+            );
+            (step * rotation).normalized()
+        }
+    }
+
+    /// Apply the rotation to the axis
+    #[inline]
+    fn rotate(up: Vector3, rotation: Quaternion) -> Vector3 {
+        rotation * up
+    }
+
+    export_gravity_up![GravityFlat3D => Vector3;
+        /// Emitted every physics frame while the axis is animated
+        #[signal]
+        fn direction_changed(up: Vector3);
+    ];
+
+    export_gravity_dict![GravityFlat3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        strength,
+        inverted,
+        one_sided,
+        plane_offset,
+        animate,
+        angular_velocity,
+    ]];
 
     gravity_field_flat! {
         GravityFlat3D where {
@@ -137,3 +383,29 @@ pub mod inner3d {
 // re-export types
 pub use inner2d::GravityFlat2D;
 pub use inner3d::GravityFlat3D;
+
+#[cfg(test)]
+mod tests {
+    use super::inner3d::flat_up;
+    use godot::builtin::{Transform3D, Vector3};
+
+    #[test]
+    fn one_sided_cuts_gravity_below_the_plane() {
+        let below = Vector3::new(0.0, -1.0, 0.0);
+        let above = Vector3::new(0.0, 1.0, 0.0);
+        assert_eq!(flat_up(Vector3::UP, true, 0.0, &below), Vector3::ZERO);
+        assert_eq!(flat_up(Vector3::UP, true, 0.0, &above), Vector3::UP);
+        assert_eq!(flat_up(Vector3::UP, false, 0.0, &below), Vector3::UP);
+    }
+
+    #[test]
+    fn one_sided_plane_follows_a_translated_field() {
+        // a field lifted to Y = 10 cuts the gravity below that height
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(3.0, 10.0, -2.0));
+        let to_local = trs.affine_inverse();
+        let below = to_local * Vector3::new(0.0, 9.0, 0.0);
+        let above = to_local * Vector3::new(0.0, 11.0, 0.0);
+        assert_eq!(flat_up(Vector3::UP, true, 0.0, &below), Vector3::ZERO);
+        assert_eq!(flat_up(Vector3::UP, true, 0.0, &above), Vector3::UP);
+    }
+}
@@ -0,0 +1,277 @@
+//!
+//! Gravity field flipping smoothly on demand
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction, orthonormal_basis},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// State of an ongoing flip
+    #[derive(Debug, Clone, Copy)]
+    pub(super) struct Flip {
+        /// UP direction when the flip started
+        from: Vector3,
+
+        /// Angle to rotate around the pivot to reach the target
+        angle: real,
+
+        /// Time elapsed since the flip started
+        elapsed: real,
+    }
+
+    impl Flip {
+        /// Start flipping from the UP direction toward the target around the pivot.
+        /// Return `None` if the flip completes instantly.
+        pub(super) fn begin(
+            from: Vector3,
+            target: Vector3,
+            pivot: Vector3,
+            duration: real,
+        ) -> Option<Self> {
+            (duration > 0.0).then(|| Self {
+                from,
+                angle: from.signed_angle_to(target, pivot),
+                elapsed: 0.0,
+            })
+        }
+
+        /// Advance the flip by the time step, return true once it is complete
+        pub(super) fn advance(&mut self, delta: real, duration: real) -> bool {
+            self.elapsed += delta;
+            self.elapsed >= duration
+        }
+
+        /// UP direction at this point of the flip
+        pub(super) fn up(&self, pivot: Vector3, duration: real) -> Vector3 {
+            let t = (self.elapsed / duration).clamp(0.0, 1.0);
+            self.from.rotated(pivot, self.angle * t)
+        }
+    }
+
+    /// Define a gravity based on an axis direction which can be
+    /// flipped smoothly over a given duration.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityFlip3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis3D,
+
+        /// Duration in seconds of a flip, zero flips instantly
+        #[export(range = (0.0, 1.0, or_greater))]
+        flip_duration: real,
+
+        /// Inverse the gravity, this is the target of an ongoing flip
+        #[export]
+        inverted: bool,
+
+        /// Ongoing flip
+        flip: Option<Flip>,
+    }
+
+    export_gravity_up![GravityFlip3D => Vector3;
+        /// Emitted when the gravity starts flipping
+        #[signal]
+        fn flip_started();
+
+        /// Emitted when the gravity is fully flipped
+        #[signal]
+        fn flip_completed();
+    ];
+
+    export_gravity_dict![GravityFlip3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        flip_duration,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityFlip3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                axis: Axis3D::Y,
+                flip_duration: 1.0,
+                inverted: false,
+                flip: None,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+
+        /// Advance the ongoing flip
+        fn physics_process(&mut self, delta: f64) {
+            let Some(flip) = self.flip.as_mut() else {
+                return;
+            };
+            if flip.advance(delta as real, self.flip_duration) {
+                self.flip = None;
+                self.base_mut().emit_signal("flip_completed", &[]);
+            }
+        }
+    }
+
+    #[godot_api(secondary)]
+    impl GravityFlip3D {
+        /// Flip the gravity smoothly over the flip duration.
+        /// Flipping again during a flip rotates back from the current direction.
+        #[func]
+        pub fn invert(&mut self) {
+            let from = self.current_up();
+            self.inverted = !self.inverted;
+            self.base_mut().emit_signal("flip_started", &[]);
+
+            self.flip = Flip::begin(from, self.target_up(), self.pivot(), self.flip_duration);
+            if self.flip.is_none() {
+                self.base_mut().emit_signal("flip_completed", &[]);
+            }
+        }
+
+        /// Check if the gravity is currently flipping
+        #[func]
+        pub fn is_flipping(&self) -> bool {
+            self.flip.is_some()
+        }
+    }
+
+    impl GravityFlip3D {
+        /// UP direction once the flip is complete
+        #[inline]
+        fn target_up(&self) -> Vector3 {
+            let up = self.axis.to_vector();
+            if self.inverted { -up } else { up }
+        }
+
+        /// Axis around which the gravity rotates while flipping
+        #[inline]
+        fn pivot(&self) -> Vector3 {
+            orthonormal_basis(&self.axis.to_vector(), &Vector3::FORWARD).col_a()
+        }
+
+        /// UP direction at this point of the flip
+        fn current_up(&self) -> Vector3 {
+            match &self.flip {
+                Some(flip) => flip.up(self.pivot(), self.flip_duration),
+                None => self.target_up(),
+            }
+        }
+    }
+
+    impl Field<Vector3> for GravityFlip3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction is defined by the axis and the progress of the flip
+        #[inline]
+        fn local_up(&self, _position: &Vector3) -> Vector3 {
+            self.current_up()
+        }
+
+        /// Up direction is defined by the axis and the progress of the flip
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityFlip3D;
+
+#[cfg(test)]
+mod tests {
+    use super::inner3d::Flip;
+    use godot::builtin::{Vector3, math::ApproxEq, real};
+
+    /// Flip from UP to DOWN around the X-axis
+    fn begin(duration: real) -> Option<Flip> {
+        Flip::begin(Vector3::UP, Vector3::DOWN, Vector3::RIGHT, duration)
+    }
+
+    #[test]
+    fn flip_fully_reverses_after_the_duration() {
+        let mut flip = begin(1.0).expect("the flip should not be instant");
+        assert!(flip.up(Vector3::RIGHT, 1.0).approx_eq(&Vector3::UP));
+
+        // halfway through the direction is perpendicular to the axis
+        assert!(!flip.advance(0.5, 1.0));
+        let halfway = flip.up(Vector3::RIGHT, 1.0);
+        assert!(halfway.is_normalized());
+        assert!(halfway.dot(Vector3::UP).abs() < 1e-5);
+
+        assert!(flip.advance(0.5, 1.0));
+        assert!(flip.up(Vector3::RIGHT, 1.0).approx_eq(&Vector3::DOWN));
+    }
+
+    #[test]
+    fn flip_completes_on_the_step_reaching_the_duration() {
+        // flip_started is emitted by invert, flip_completed once advance reports it
+        let mut flip = begin(1.0).expect("the flip should not be instant");
+        let completed: Vec<bool> = (0..4).map(|_| flip.advance(0.25, 1.0)).collect();
+        assert_eq!(completed, [false, false, false, true]);
+    }
+
+    #[test]
+    fn flip_without_duration_completes_instantly() {
+        assert!(begin(0.0).is_none());
+    }
+}
@@ -0,0 +1,218 @@
+//!
+//! Heightmap gravity field
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        builtin::math::FloatExt,
+        classes::{Area3D, IArea3D, Image},
+        prelude::*,
+    };
+
+    /// Pixel coordinates of a position in the local space of the field, for a terrain
+    /// of the given size centered on the origin. `None` outside of the terrain.
+    pub(super) fn heightmap_pixel(
+        size: Vector2,
+        cell: Vector2,
+        max: Vector2,
+        position: &Vector3,
+    ) -> Option<Vector2> {
+        let pixel = (Vector2::new(position.x, position.z) + size * 0.5) / cell;
+        let inside = pixel.x >= 0.0 && pixel.y >= 0.0 && pixel.x <= max.x && pixel.y <= max.y;
+        inside.then_some(pixel)
+    }
+
+    /// Define a gravity following the normal of a terrain described by a heightmap.
+    /// The heightmap covers the XZ plane and is centered on the origin of the area.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityHeightmap3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Grayscale image describing the height of the terrain,
+        /// it must be uncompressed to be sampled.
+        #[export]
+        heightmap: Option<Gd<Image>>,
+
+        /// Size of the terrain on the X and Z axes
+        #[export]
+        size: Vector2,
+
+        /// Height of the terrain for a white pixel
+        #[export(range = (0.0, 10.0, or_greater))]
+        height: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityHeightmap3D => Vector3];
+
+    export_gravity_dict![GravityHeightmap3D => [
+        level,
+        affects_groups,
+        channel,
+        heightmap,
+        size,
+        height,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityHeightmap3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                heightmap: None,
+                size: Vector2::ONE,
+                height: 1.0,
+                inverted: false,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    impl Field<Vector3> for GravityHeightmap3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction is the normal of the terrain below the position.
+        /// Default to the Y axis outside of the terrain or without heightmap.
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = self.surface_normal(position).unwrap_or(Vector3::UP);
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction is the normal of the terrain
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+
+    impl GravityHeightmap3D {
+        /// Compute the normal of the terrain at the XZ coordinates of the position
+        /// in the local space of the field
+        /// from the gradient of the heightmap, using central differences.
+        fn surface_normal(&self, position: &Vector3) -> Option<Vector3> {
+            let image = self.heightmap.as_ref()?;
+            let (width, depth) = (image.get_width(), image.get_height());
+            if width < 2 || depth < 2 || self.size.x <= 0.0 || self.size.y <= 0.0 {
+                return None;
+            }
+
+            // convert the position into pixel coordinates
+            let cell = Vector2::new(
+                self.size.x / (width - 1) as real,
+                self.size.y / (depth - 1) as real,
+            );
+            let max = Vector2::new((width - 1) as real, (depth - 1) as real);
+            let pixel = heightmap_pixel(self.size, cell, max, position)?;
+
+            // finite differences of the height over one pixel
+            let sample = |x: real, y: real| {
+                self.sample_height(image, x.clamp(0.0, max.x), y.clamp(0.0, max.y))
+            };
+            let dx =
+                (sample(pixel.x + 1.0, pixel.y) - sample(pixel.x - 1.0, pixel.y)) / (2.0 * cell.x);
+            let dz =
+                (sample(pixel.x, pixel.y + 1.0) - sample(pixel.x, pixel.y - 1.0)) / (2.0 * cell.y);
+            Some(Vector3::new(-dx, 1.0, -dz).normalized())
+        }
+
+        /// Bilinearly interpolate the height of the terrain at the given pixel coordinates
+        fn sample_height(&self, image: &Gd<Image>, x: real, y: real) -> real {
+            let (x0, y0) = (x.floor() as i32, y.floor() as i32);
+            let (x1, y1) = (
+                (x0 + 1).min(image.get_width() - 1),
+                (y0 + 1).min(image.get_height() - 1),
+            );
+            let (tx, ty) = (x - x0 as real, y - y0 as real);
+
+            let pixel = |x: i32, y: i32| image.get_pixel(x, y).r as real;
+            let top = pixel(x0, y0).lerp(pixel(x1, y0), tx);
+            let bottom = pixel(x0, y1).lerp(pixel(x1, y1), tx);
+            top.lerp(bottom, ty) * self.height
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityHeightmap3D;
+
+#[cfg(test)]
+mod tests {
+    use super::inner3d::heightmap_pixel;
+    use godot::builtin::{Transform3D, Vector2, Vector3};
+
+    #[test]
+    fn heightmap_is_sampled_around_a_moved_field() {
+        // a 10x10 terrain of 11x11 pixels moved to X = 100
+        let (size, cell, max) = (Vector2::ONE * 10.0, Vector2::ONE, Vector2::ONE * 10.0);
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(100.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(100.0, 3.0, 0.0);
+        assert_eq!(
+            heightmap_pixel(size, cell, max, &local),
+            Some(Vector2::new(5.0, 5.0))
+        );
+
+        // the world origin is beyond the edge of the terrain
+        let outside = trs.affine_inverse() * Vector3::ZERO;
+        assert_eq!(heightmap_pixel(size, cell, max, &outside), None);
+    }
+}
@@ -0,0 +1,166 @@
+//!
+//! Gravity field perturbing another field with noise
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            query::DynGravityField3D,
+            util::{in_any_group, util3d::orthonormal_basis},
+        },
+    };
+    use godot::{
+        builtin::real_consts,
+        classes::{Area3D, FastNoiseLite, IArea3D},
+        prelude::*,
+    };
+
+    /// Offset between the samples of the noise used for the tilt and its orientation
+    const TWIST_OFFSET: Vector3 = Vector3::new(137.0, 59.0, 211.0);
+
+    /// Define a gravity wobbling the direction of another field with noise.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityNoise3D {
+        base: Base<Area3D>,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Gravity field whose direction is perturbed, it defines the priority level
+        #[export]
+        field: Option<DynGravityField3D>,
+
+        /// Noise sampled at the queried position
+        #[export]
+        noise: Option<Gd<FastNoiseLite>>,
+
+        /// Maximum tilt of the direction in radians
+        #[export(range = (0.0, 180.0, radians_as_degrees))]
+        amplitude: real,
+
+        /// Speed at which the noise scrolls over time, zero freezes it
+        #[export]
+        time_scale: real,
+
+        /// Time elapsed since the node entered the tree
+        elapsed: real,
+    }
+
+    export_gravity_up![GravityNoise3D => Vector3];
+
+    export_gravity_dict![GravityNoise3D => [
+        affects_groups,
+        channel,
+        field,
+        noise,
+        amplitude,
+        time_scale,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityNoise3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                affects_groups: Array::new(),
+                channel: 0,
+                field: None,
+                noise: None,
+                amplitude: 0.2,
+                time_scale: 0.0,
+                elapsed: 0.0,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+
+        /// Scroll the noise over time
+        fn physics_process(&mut self, delta: f64) {
+            if self.time_scale != 0.0 {
+                self.elapsed += delta as real;
+            }
+        }
+    }
+
+    impl Field<Vector3> for GravityNoise3D {
+        /// Priority level of the wrapped field
+        #[inline]
+        fn level(&self) -> Level {
+            self.field
+                .as_ref()
+                .map_or(Level::MIN, |field| field.dyn_bind().level())
+        }
+
+        /// Up direction in the local space of this node
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            self.base().get_global_basis().inverse() * self.global_up(position)
+        }
+
+        /// Up direction of the wrapped field tilted by the noise
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            let Some(field) = self.field.as_ref() else {
+                return Vector3::ZERO;
+            };
+            let up = field.dyn_bind().global_up(position);
+            let Some(noise) = self.noise.as_ref().filter(|_| !up.is_zero_approx()) else {
+                return up;
+            };
+            let direction = up.normalized();
+
+            // tilt the direction around an axis spinning around it
+            let sample = *position + Vector3::ONE * (self.elapsed * self.time_scale);
+            let angle = self.amplitude * noise.get_noise_3dv(sample) as real;
+            let twist = real_consts::PI * noise.get_noise_3dv(sample + TWIST_OFFSET) as real;
+            let axis = orthonormal_basis(&direction, &Vector3::FORWARD)
+                .col_a()
+                .rotated(direction, twist);
+            up.rotated(axis, angle)
+        }
+
+        /// Center of the wrapped field
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            self.field.as_ref().map_or_else(
+                || self.base().get_global_position(),
+                |field| field.dyn_bind().global_center(),
+            )
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+
+        /// Strength of the wrapped field
+        #[inline]
+        fn strength(&self, position: &Vector3) -> real {
+            self.field
+                .as_ref()
+                .map_or(0.0, |field| field.dyn_bind().strength(position))
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityNoise3D;
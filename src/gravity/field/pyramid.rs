@@ -0,0 +1,169 @@
+//!
+//! Pyramid gravity field
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            math::math3d::pyramid_up,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity normal to the faces of a square pyramid or frustum
+    /// standing on the axis, with its base centered on the origin.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityPyramid3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis3D,
+
+        /// Size of the base of the pyramid
+        #[export]
+        base_size: Vector2,
+
+        /// Size of the top of the pyramid, zero makes a pointy pyramid
+        #[export]
+        top_size: Vector2,
+
+        /// Height of the pyramid along the axis
+        #[export(range = (0.0, 10.0, or_greater))]
+        height: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityPyramid3D => Vector3];
+
+    export_gravity_dict![GravityPyramid3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        base_size,
+        top_size,
+        height,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityPyramid3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                axis: Axis3D::Y,
+                base_size: Vector2::new(2.0, 2.0),
+                top_size: Vector2::ZERO,
+                height: 1.0,
+                inverted: false,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    impl Field<Vector3> for GravityPyramid3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction is normal to one of the sloped sides or caps of the pyramid
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = pyramid_up(
+                position,
+                self.axis,
+                &self.base_size,
+                &self.top_size,
+                self.height,
+            );
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction is normal to one of the faces of the pyramid
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityPyramid3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::{axis::Axis3D, math::math3d::pyramid_up};
+    use godot::builtin::{Transform3D, Vector2, Vector3};
+
+    #[test]
+    fn pyramid_picks_the_face_of_a_moved_field() {
+        // the global position lies under the base of a pyramid moved to X = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(10.0, -1.0, 0.0);
+        let up = pyramid_up(
+            &local,
+            Axis3D::Y,
+            &(Vector2::ONE * 2.0),
+            &Vector2::ZERO,
+            2.0,
+        );
+        assert_eq!(up, Vector3::DOWN);
+    }
+}
@@ -8,7 +8,28 @@ pub mod cuboid;
 /// Define a shaped backed by a curve
 pub mod curve;
 
-use godot::obj::{Gd, GodotClass};
+use godot::{
+    obj::{Gd, GodotClass},
+    prelude::{Export, GString, GodotConvert, Var},
+};
+use std::ops::Neg;
+
+/// Select how the colliders of a shape are generated
+#[repr(C)]
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[godot(via = GString)]
+pub enum ColliderPrecision {
+    /// Compose the surface with many primitive shapes (boxes, capsules, ...).
+    #[default]
+    Primitives,
+
+    /// Generate a single concave shape from a tessellated surface.
+    /// This reduces the shape count at the cost of concave collision performance.
+    Trimesh,
+}
+
+/// Number of segments used to tessellate a quarter of a circle in trimesh colliders
+pub(crate) const TRIMESH_SEGMENTS: usize = 4;
 
 /// Trait to implement a shape for a gravity field
 pub trait Shape<V, Shp, Trs>
@@ -20,6 +41,20 @@ where
 
     /// Get the list of colliders to generate a static body.
     fn colliders(&mut self) -> Vec<(Gd<Shp>, Trs)>;
+
+    /// Get the half extents of the box enclosing the colliders,
+    /// centered on the origin of the shape.
+    #[inline]
+    fn extents(&self) -> Option<V> {
+        None
+    }
+
+    /// Check if the position in the local space of the shape is within
+    /// the volume enclosed by its colliders.
+    #[inline]
+    fn contains(&self, _position: &V) -> bool {
+        false
+    }
 }
 
 /// Interface for internal shape representation
@@ -31,17 +66,30 @@ where
     fn colliders(&self) -> Vec<(Gd<Shp>, Trs)>;
 }
 
+/// Orient the UP direction of a shape, negated if the field is inverted.
+/// A null direction stays null.
+#[inline]
+pub(crate) fn oriented_up<V: Neg<Output = V>>(up: V, inverted: bool) -> V {
+    if inverted { -up } else { up }
+}
+
 macro_rules! gravity_field_shaped {
     (
         $shape_type:ty where {
             $area:ty | $area_interface:ty,
             $vector:ty,
-            $dynamic_type:ident
+            $transform:ty,
+            $dynamic_type:ident,
+            $body:ty,
+            $collision:ty
         }
     ) => {
         // alias provided type
         type Vector = $vector;
         type Area = $area;
+        type Transform = $transform;
+        type StaticBody = $body;
+        type CollisionShape = $collision;
 
         #[godot_api]
         impl $area_interface for $shape_type {
@@ -50,11 +98,23 @@ macro_rules! gravity_field_shaped {
                 Self {
                     base,
                     level: 0,
+                    affects_groups: Array::new(),
+                    channel: 0,
                     shape: None,
                     build_collider: false,
+                    collider_layer: 1,
+                    collider_mask: 1,
                     inverted: false,
+                    body: None,
                 }
             }
+
+            /// Generate the static body and forward the body signals
+            /// once the node enters the tree.
+            fn ready(&mut self) {
+                self.connect_gravity_signals();
+                self.rebuild_collider();
+            }
         }
 
         impl Field<Vector> for $shape_type {
@@ -64,30 +124,175 @@ macro_rules! gravity_field_shaped {
                 self.level
             }
 
-            /// Up direction is solely defined by the axis selected
-            fn local_up(&self, _position: &Vector) -> Vector {
-                let up = Vector::ZERO;
+            /// Up direction is defined by the shape resource, no gravity without a shape
+            fn local_up(&self, position: &Vector) -> Vector {
+                let up = self
+                    .shape
+                    .as_ref()
+                    .map_or(Vector::ZERO, |shape| shape.dyn_bind().up(position));
 
                 // Check if the direction should be inverted
-                if self.inverted { -up } else { up }
+                oriented_up(up, self.inverted)
             }
 
-            /// Up direction is solely defined by the axis selected
+            /// Up direction is defined by the shape resource
             fn global_up(&self, position: &Vector) -> Vector {
                 global_direction(self, position)
             }
+
+            /// Center is the origin of the area
+            #[inline]
+            fn global_center(&self) -> Vector {
+                global_center(self)
+            }
+
+            /// Only affect the nodes in the selected groups
+            #[inline]
+            fn affects(&self, node: &Gd<Node>) -> bool {
+                in_any_group(&self.affects_groups, node)
+            }
+
+            /// Get the gravity channel
+            #[inline]
+            fn channel(&self) -> Channel {
+                self.channel
+            }
+
+            /// Bounds enclose the colliders of the shape
+            fn bounds(&self) -> Option<<Vector as WithBounds>::Bounds> {
+                let extents = self.shape.as_ref()?.dyn_bind().extents()?;
+                Some(global_bounds(self, Vector::ZERO, extents))
+            }
         }
 
-        #[godot_api]
+        #[godot_api(secondary)]
         impl $shape_type {
             #[func]
-            pub fn get_up_direction(&self, position: Vector) -> Vector {
-                self.global_up(&position)
+            fn set_build_collider(&mut self, set: bool) {
+                self.build_collider = set;
+                if self.base().is_node_ready() {
+                    self.rebuild_collider();
+                }
             }
 
             #[func]
-            fn set_build_collider(&mut self, set: bool) {
-                self.build_collider = set;
+            fn set_collider_layer(&mut self, layer: Mask) {
+                self.collider_layer = layer;
+                if let Some(body) = self.body.as_mut().filter(|body| body.is_instance_valid()) {
+                    body.set_collision_layer(layer);
+                }
+            }
+
+            #[func]
+            fn set_collider_mask(&mut self, mask: Mask) {
+                self.collider_mask = mask;
+                if let Some(body) = self.body.as_mut().filter(|body| body.is_instance_valid()) {
+                    body.set_collision_mask(mask);
+                }
+            }
+
+            #[func]
+            fn set_shape(&mut self, shape: Option<$dynamic_type>) {
+                // Follow the changes of the new shape instead of the previous one.
+                // The rebuild is deferred since the shape is still bound while
+                // its setter emits the signal.
+                let callable = self.base().callable("on_shape_changed");
+                if let Some(mut previous) = self.shape.take() {
+                    if previous.is_connected("changed", &callable) {
+                        previous.disconnect("changed", &callable);
+                    }
+                }
+                if let Some(mut shape) = shape.clone() {
+                    shape
+                        .connect_ex("changed", &callable)
+                        .flags(ConnectFlags::DEFERRED.ord() as u32)
+                        .done();
+                }
+
+                self.shape = shape;
+                if self.base().is_node_ready() {
+                    self.rebuild_collider();
+                }
+            }
+
+            /// Rebuild the static body once the shape resource has changed
+            #[func]
+            fn on_shape_changed(&mut self) {
+                if self.base().is_node_ready() {
+                    self.rebuild_collider();
+                }
+            }
+
+            /// Discard the generated static body and build a new one
+            /// from the colliders of the shape if `build_collider` is set.
+            #[func]
+            pub fn rebuild_collider(&mut self) {
+                if let Some(mut body) = self.body.take() {
+                    if body.is_instance_valid() {
+                        body.queue_free();
+                    }
+                }
+
+                if !self.build_collider {
+                    return;
+                }
+                let Some(shape) = self.shape.as_mut() else {
+                    return;
+                };
+                let colliders = shape.dyn_bind_mut().colliders();
+
+                let mut body = StaticBody::new_alloc();
+                body.set_collision_layer(self.collider_layer);
+                body.set_collision_mask(self.collider_mask);
+                for (collider, trs) in colliders {
+                    let mut collision = CollisionShape::new_alloc();
+                    collision.set_shape(&collider);
+                    collision.set_transform(trs);
+                    body.add_child(&collision);
+                }
+                self.base_mut().add_child(&body);
+                self.body = Some(body);
+            }
+
+            /// Check if the position in global space is within the shape,
+            /// without performing a physics query. False without a shape.
+            #[func]
+            pub fn contains_point(&self, position: Vector) -> bool {
+                let local = local_position(self, &position);
+                self.shape
+                    .as_ref()
+                    .is_some_and(|shape| shape.dyn_bind().contains(&local))
+            }
+
+            /// Get the transforms of the colliders generated by the shape,
+            /// suitable for the instances of a MultiMesh.
+            #[func]
+            pub fn colliders_as_multimesh_transforms(&mut self) -> Array<Transform> {
+                self.collider_transforms().into_iter().collect()
+            }
+
+            /// Fill the multimesh with one instance per collider generated by the shape.
+            /// Return the number of instances.
+            #[func]
+            pub fn fill_multimesh(&mut self, mut multimesh: Gd<MultiMesh>) -> i32 {
+                let transforms = self.collider_transforms();
+                populate_multimesh(&mut multimesh, &transforms);
+                transforms.len() as i32
+            }
+        }
+
+        impl $shape_type {
+            /// Get the transforms of the colliders generated by the shape
+            pub fn collider_transforms(&mut self) -> Vec<Transform> {
+                match self.shape.as_mut() {
+                    Some(shape) => shape
+                        .dyn_bind_mut()
+                        .colliders()
+                        .into_iter()
+                        .map(|(_, trs)| trs)
+                        .collect(),
+                    None => Vec::new(),
+                }
             }
         }
     };
@@ -95,11 +300,26 @@ macro_rules! gravity_field_shaped {
 
 pub mod inner2d {
 
-    use super::Shape;
-    use crate::gravity::{Field, Level, util::util2d::global_direction};
+    use super::{Shape, oriented_up};
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level, Mask, WithBounds,
+            util::{
+                in_any_group,
+                util2d::{
+                    global_bounds, global_center, global_direction, local_position,
+                    populate_multimesh,
+                },
+            },
+        },
+    };
     use godot::{
-        classes::{Area2D, IArea2D, Resource, Shape2D},
-        obj::DynGd,
+        classes::{
+            Area2D, CollisionShape2D, IArea2D, MultiMesh, Resource, Shape2D, StaticBody2D,
+            object::ConnectFlags,
+        },
+        obj::{DynGd, EngineBitfield},
         prelude::*,
     };
 
@@ -116,8 +336,19 @@ pub mod inner2d {
         #[export]
         level: Level,
 
-        /// The shape definition to use
+        /// Groups of the nodes affected by the gravity, empty means any node
         #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// The shape definition to use,
+        /// the static body is rebuilt whenever the shape changes.
+        #[export]
+        #[var(get, set = set_shape)]
         shape: Option<DynShape2D>,
 
         /// Specify if a static body should be generated
@@ -125,27 +356,71 @@ pub mod inner2d {
         #[var(get, set = set_build_collider)]
         build_collider: bool,
 
+        /// Collision layer of the generated static body
+        #[export(flags_2d_physics)]
+        #[var(get, set = set_collider_layer)]
+        collider_layer: Mask,
+
+        /// Collision mask of the generated static body
+        #[export(flags_2d_physics)]
+        #[var(get, set = set_collider_mask)]
+        collider_mask: Mask,
+
         /// Inverse the gravity
         #[export]
         inverted: bool,
+
+        /// Static body generated from the colliders of the shape
+        body: Option<Gd<StaticBody2D>>,
     }
 
+    export_gravity_up![GravityShaped2D => Vector2];
+
+    export_gravity_dict![GravityShaped2D => [
+        level,
+        affects_groups,
+        channel,
+        shape,
+        build_collider,
+        collider_layer,
+        collider_mask,
+        inverted,
+    ]];
+
     gravity_field_shaped! {
         GravityShaped2D where {
             Area2D | IArea2D,
             Vector2,
-            DynShape2D
+            Transform2D,
+            DynShape2D,
+            StaticBody2D,
+            CollisionShape2D
         }
     }
 }
 
 pub mod inner3d {
 
-    use super::Shape;
-    use crate::gravity::{Field, Level, util::util3d::global_direction};
+    use super::{Shape, oriented_up};
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level, Mask, WithBounds,
+            util::{
+                in_any_group,
+                util3d::{
+                    global_bounds, global_center, global_direction, local_position,
+                    populate_multimesh,
+                },
+            },
+        },
+    };
     use godot::{
-        classes::{Area3D, IArea3D, Resource, Shape3D},
-        obj::DynGd,
+        classes::{
+            Area3D, CollisionShape3D, IArea3D, MultiMesh, Resource, Shape3D, StaticBody3D,
+            object::ConnectFlags,
+        },
+        obj::{DynGd, EngineBitfield},
         prelude::*,
     };
 
@@ -162,8 +437,19 @@ pub mod inner3d {
         #[export]
         level: Level,
 
-        /// The shape definition to use
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// The shape definition to use,
+        /// the static body is rebuilt whenever the shape changes.
         #[export]
+        #[var(get, set = set_shape)]
         shape: Option<DynShape3D>,
 
         /// Specify if a static body should be generated
@@ -171,16 +457,45 @@ pub mod inner3d {
         #[var(get, set = set_build_collider)]
         build_collider: bool,
 
+        /// Collision layer of the generated static body
+        #[export(flags_3d_physics)]
+        #[var(get, set = set_collider_layer)]
+        collider_layer: Mask,
+
+        /// Collision mask of the generated static body
+        #[export(flags_3d_physics)]
+        #[var(get, set = set_collider_mask)]
+        collider_mask: Mask,
+
         /// Inverse the gravity
         #[export]
         inverted: bool,
+
+        /// Static body generated from the colliders of the shape
+        body: Option<Gd<StaticBody3D>>,
     }
 
+    export_gravity_up![GravityShaped3D => Vector3];
+
+    export_gravity_dict![GravityShaped3D => [
+        level,
+        affects_groups,
+        channel,
+        shape,
+        build_collider,
+        collider_layer,
+        collider_mask,
+        inverted,
+    ]];
+
     gravity_field_shaped! {
         GravityShaped3D where {
             Area3D | IArea3D,
             Vector3,
-            DynShape3D
+            Transform3D,
+            DynShape3D,
+            StaticBody3D,
+            CollisionShape3D
         }
     }
 }
@@ -188,3 +503,54 @@ pub mod inner3d {
 // re-export
 pub use inner2d::{DynShape2D, GravityShaped2D};
 pub use inner3d::{DynShape3D, GravityShaped3D};
+
+#[cfg(test)]
+mod tests {
+    use super::oriented_up;
+    use crate::gravity::math::{math2d, math3d};
+    use godot::builtin::{Transform3D, Vector2, Vector3};
+
+    #[test]
+    fn inverted_flips_every_cuboid_3d_direction() {
+        let half = Vector3::new(1.0, 2.0, 0.5);
+        for position in [
+            Vector3::new(3.0, 0.0, 0.0),
+            Vector3::new(2.0, 3.0, 0.0),
+            Vector3::new(-2.0, 3.0, 1.0),
+            Vector3::new(0.2, -1.5, 0.1),
+        ] {
+            let up = math3d::cuboid_up(&half, &position);
+            assert_eq!(oriented_up(up, false), up);
+            assert_eq!(oriented_up(up, true), -up);
+        }
+    }
+
+    #[test]
+    fn inverted_flips_every_cuboid_2d_direction() {
+        let half = Vector2::new(1.0, 2.0);
+        for position in [
+            Vector2::new(3.0, 0.0),
+            Vector2::new(2.0, -3.0),
+            Vector2::new(0.2, 1.5),
+        ] {
+            let up = math2d::cuboid_up(&half, &position);
+            assert_eq!(oriented_up(up, false), up);
+            assert_eq!(oriented_up(up, true), -up);
+        }
+    }
+
+    #[test]
+    fn cuboid_follows_a_moved_field() {
+        // the global position lies left of a cuboid moved to X = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(8.0, 0.0, 0.0);
+        let half = Vector3::new(1.0, 1.0, 1.0);
+        assert_eq!(math3d::cuboid_up(&half, &local), Vector3::LEFT);
+    }
+
+    #[test]
+    fn inverted_keeps_a_null_direction_null() {
+        assert_eq!(oriented_up(Vector3::ZERO, true), Vector3::ZERO);
+        assert_eq!(oriented_up(Vector2::ZERO, true), Vector2::ZERO);
+    }
+}
@@ -15,22 +15,28 @@ macro_rules! shape_cuboid {
         type Transform = $transform;
         type Vector = $vector;
         type GShape = $shape;
+        type Bounds = <Vector as WithBounds>::Bounds;
 
         /// Define a gravity based on an axis direction.
+        /// Inside of the cuboid the gravity points out of the nearest face.
+        /// On a tie between faces, such as at the center,
+        /// the positive side of the first axis wins.
         #[derive(GodotClass)]
         #[class(base=Resource)]
         pub struct $shape_type {
             base: Base<Resource>,
 
-            /// Generated shapes
-            internal: Option<Internal>,
+            /// Generated shapes, shared with the cuboids of identical parameters
+            internal: Option<Rc<Internal>>,
 
-            /// Define the size of the cuboid.
+            /// Full size of the cuboid, rounded edges excluded.
             #[export]
             #[var(get, set = set_box_size)]
             box_size: Vector,
 
             /// Radius of the edges for rounding the box.
+            /// The rounding grows the box outward, so any radius is valid,
+            /// even one larger than half of the box size.
             #[export(range = (0.0, 1.0, or_greater))]
             #[var(get, set = set_edge_radius)]
             edge_radius: real,
@@ -40,8 +46,29 @@ macro_rules! shape_cuboid {
             #[export]
             #[var(get, set = set_hollow)]
             hollow: bool,
+
+            /// Generate primitive shapes or a single trimesh.
+            /// A trimesh only describes the surface and is therefore always hollow.
+            #[export]
+            #[var(get, set = set_collider_precision)]
+            collider_precision: ColliderPrecision,
+
+            /// Uniform scale applied to the generated colliders and to the UP computation.
+            /// This is applied on top of the scale of the area node, prefer this one
+            /// as physics engines handle scaled collision shapes poorly.
+            #[export(range = (0.01, 1.0, or_greater))]
+            #[var(get, set = set_collider_scale)]
+            collider_scale: real,
         }
 
+        crate::export_gravity_dict![$shape_type => [
+            box_size,
+            edge_radius,
+            hollow,
+            collider_precision,
+            collider_scale,
+        ]];
+
         #[godot_api]
         impl IResource for $shape_type {
             fn init(base: Base<Resource>) -> Self {
@@ -51,6 +78,8 @@ macro_rules! shape_cuboid {
                     box_size: Vector::ONE,
                     edge_radius: 0.0,
                     hollow: false,
+                    collider_precision: ColliderPrecision::Primitives,
+                    collider_scale: 1.0,
                 }
             }
         }
@@ -61,18 +90,147 @@ macro_rules! shape_cuboid {
             fn set_box_size(&mut self, size: Vector) {
                 self.box_size = size.coord_max(MIN_SIZE);
                 self.internal = None;
+                self.base_mut().emit_changed();
             }
 
             #[func]
             fn set_edge_radius(&mut self, radius: real) {
-                self.edge_radius = radius;
+                self.edge_radius = radius.max(0.0);
                 self.internal = None;
+                self.base_mut().emit_changed();
             }
 
             #[func]
             fn set_hollow(&mut self, hollow: bool) {
                 self.hollow = hollow;
                 self.internal = None;
+                self.base_mut().emit_changed();
+            }
+
+            #[func]
+            fn set_collider_precision(&mut self, precision: ColliderPrecision) {
+                self.collider_precision = precision;
+                self.internal = None;
+                self.base_mut().emit_changed();
+            }
+
+            #[func]
+            fn set_collider_scale(&mut self, scale: real) {
+                self.collider_scale = scale.max(real::MIN_POSITIVE);
+                self.internal = None;
+                self.base_mut().emit_changed();
+            }
+
+            /// Get the colliders generated by the shape, each one described by
+            /// a dictionary with the class name of its shape and its transform.
+            #[func]
+            pub fn get_colliders(&mut self) -> Array<Dictionary> {
+                self.colliders()
+                    .into_iter()
+                    .map(|(shape, trs)| {
+                        vdict! {
+                            "class": shape.get_class(),
+                            "transform": trs
+                        }
+                    })
+                    .collect()
+            }
+
+            /// Get the number of colliders the shape generates,
+            /// computed from its parameters without generating them.
+            #[func]
+            pub fn estimated_collider_count(&self) -> i32 {
+                match self.collider_precision {
+                    ColliderPrecision::Trimesh => 1,
+                    ColliderPrecision::Primitives if self.scaled_radius() <= 0.0 => 1,
+                    ColliderPrecision::Primitives if self.hollow => ROUNDED_EDGES,
+                    ColliderPrecision::Primitives => ROUNDED_FACES + ROUNDED_EDGES,
+                }
+            }
+
+            /// Get the half extents of the generated colliders,
+            /// including the collider scale and the rounded edges.
+            #[func]
+            pub fn get_half_extents(&self) -> Vector {
+                self.half_extents()
+            }
+
+            /// Get the bounding box of the generated colliders centered on the origin,
+            /// a `Rect2` in 2D and an `Aabb` in 3D.
+            #[func]
+            pub fn get_collider_aabb(&self) -> Bounds {
+                let half = self.half_extents();
+                Bounds::new(-half, half * 2.0)
+            }
+        }
+
+        impl $shape_type {
+            /// Size of the cuboid once scaled
+            #[inline]
+            fn scaled_size(&self) -> Vector {
+                self.box_size * self.collider_scale
+            }
+
+            /// Radius of the edges once scaled
+            #[inline]
+            fn scaled_radius(&self) -> real {
+                self.edge_radius * self.collider_scale
+            }
+
+            /// Half extents of the generated colliders, rounded edges included
+            #[inline]
+            fn half_extents(&self) -> Vector {
+                collider_half_extents(&self.scaled_size(), self.scaled_radius())
+            }
+        }
+
+        /// Half extents of the colliders of a cuboid of the given size and edge radius
+        #[inline]
+        pub(super) fn collider_half_extents(size: &Vector, radius: real) -> Vector {
+            *size * 0.5 + Vector::ONE * radius
+        }
+
+        /// Key identifying the parameters of the internal shapes
+        type Key = ([u64; DIMENSIONS], u64, bool, ColliderPrecision);
+
+        thread_local! {
+            /// Internal shapes shared between the cuboids with identical parameters.
+            /// Only weak handles are kept, so the shapes are released along with
+            /// the last cuboid using them and the stale entries are dropped.
+            static INTERNER: RefCell<HashMap<Key, Weak<Internal>>> = RefCell::new(HashMap::new());
+        }
+
+        impl Internal {
+            /// Create the internal shapes, or reuse the ones of an identical cuboid
+            fn interned(
+                size: &Vector,
+                radius: real,
+                hollow: bool,
+                precision: ColliderPrecision,
+            ) -> Rc<Self> {
+                let key = (
+                    size_key(size),
+                    u64::from(radius.to_bits()),
+                    hollow,
+                    precision,
+                );
+                INTERNER.with_borrow_mut(|interner| {
+                    // forget the shapes no cuboid uses anymore
+                    interner.retain(|_, internal| internal.strong_count() > 0);
+                    if let Some(internal) = interner.get(&key).and_then(Weak::upgrade) {
+                        return internal;
+                    }
+
+                    let internal = Rc::new(match precision {
+                        ColliderPrecision::Trimesh => Self::new_trimesh(size, radius),
+                        ColliderPrecision::Primitives if radius > 0.0 => {
+                            Self::new_rounded(size, radius, hollow)
+                        }
+                        ColliderPrecision::Primitives => Self::new_simple(size),
+                    });
+                    interner.insert(key, Rc::downgrade(&internal));
+                    internal
+                })
             }
         }
 
@@ -87,20 +245,30 @@ macro_rules! shape_cuboid {
             fn colliders(&mut self) -> Vec<(Gd<GShape>, Transform)> {
                 // Recompute the internal shapes if requested
                 if self.internal.is_none() {
-                    self.internal = if self.edge_radius > 0.0 {
-                        Some(Internal::new_rounded(
-                            &self.box_size,
-                            self.edge_radius,
-                            self.hollow,
-                        ))
-                    } else {
-                        Some(Internal::new_simple(&self.box_size))
-                    };
+                    let (size, radius) = (self.scaled_size(), self.scaled_radius());
+                    self.internal = Some(Internal::interned(
+                        &size,
+                        radius,
+                        self.hollow,
+                        self.collider_precision,
+                    ));
                 }
 
                 // Ask the internal shape for its colliders set
                 self.internal.as_ref().unwrap().colliders()
             }
+
+            /// Half extents of the box and its rounded edges
+            #[inline]
+            fn extents(&self) -> Option<Vector> {
+                Some(self.half_extents())
+            }
+
+            /// Check if the position is within the distance of the edge radius to the box
+            fn contains(&self, position: &Vector) -> bool {
+                let outside = (position.abs() - self.scaled_size() * 0.5).coord_max(Vector::ZERO);
+                outside.length() <= self.scaled_radius()
+            }
         }
     };
 }
@@ -108,13 +276,40 @@ macro_rules! shape_cuboid {
 pub mod inner2d {
 
     use crate::{
-        gravity::{build_trs::TransformBuilder2D, field::shaped::Shape, util::util2d::*},
+        gravity::{
+            WithBounds,
+            build_trs::TransformBuilder2D,
+            field::shaped::{ColliderPrecision, Shape, TRIMESH_SEGMENTS},
+            math::math2d::cuboid_up,
+            util::util2d::*,
+        },
         unit,
     };
     use godot::{
-        classes::{CapsuleShape2D, RectangleShape2D, Shape2D},
+        builtin::real_consts::FRAC_PI_2,
+        classes::{CapsuleShape2D, ConcavePolygonShape2D, RectangleShape2D, Shape2D},
         prelude::*,
     };
+    use std::{
+        cell::RefCell,
+        collections::HashMap,
+        rc::{Rc, Weak},
+    };
+
+    /// Number of coordinates of the vectors
+    const DIMENSIONS: usize = 2;
+
+    /// Number of boxes of a filled rounded cuboid, the box filling the rectangle
+    const ROUNDED_FACES: i32 = 1;
+
+    /// Number of capsules of a rounded cuboid, one capsule per side
+    const ROUNDED_EDGES: i32 = 4;
+
+    /// Identify the size of a cuboid by the bits of its coordinates
+    #[inline]
+    fn size_key(size: &Vector2) -> [u64; DIMENSIONS] {
+        [u64::from(size.x.to_bits()), u64::from(size.y.to_bits())]
+    }
 
     shape_cuboid! {
         GravityShapedCuboid2D where {
@@ -125,49 +320,105 @@ pub mod inner2d {
     }
 
     impl GravityShapedCuboid2D {
-        /// Pick the UP direction for a cuboid
+        /// Pick the UP direction for a cuboid, the size is halved
+        /// since the math compares it to coordinates relative to the center.
         fn up_func(&self, position: &Vector2) -> Vector2 {
-            // use a bitmask to deduce the strategy to use
-            let mut mask = 0b00;
-            macro_rules! set {
-                ( $coord:ident => $bit:literal ) => {
-                    if position.$coord.abs() > self.box_size.$coord {
-                        mask |= $bit;
-                    }
-                };
+            cuboid_up(&(self.scaled_size() * 0.5), position)
+        }
+    }
+
+    /// Segments of the outline of a rounded rectangle, by pairs of points
+    pub(super) fn trimesh_segments(size: &Vector2, radius: real) -> Vec<Vector2> {
+        const Q: usize = TRIMESH_SEGMENTS;
+        const SIGNS: [(real, real); 4] = [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)];
+        let half = *size * 0.5;
+
+        // Compute the outline quadrant by quadrant,
+        // each arc is offset toward its corner of the rectangle.
+        let count = 4 * (Q + 1);
+        let mut outline = Vec::with_capacity(count);
+        for (quad, (sx, sy)) in SIGNS.iter().enumerate() {
+            let corner = Vector2::new(half.x * sx, half.y * sy);
+            for j in 0..=Q {
+                let angle = (quad as real + j as real / Q as real) * FRAC_PI_2;
+                outline.push(corner + Vector2::new(angle.cos(), angle.sin()) * radius);
+            }
+        }
+
+        // Link the points of the outline, skipping the degenerated segments
+        let mut segments = Vec::with_capacity(2 * count);
+        for i in 0..count {
+            let (a, b) = (outline[i], outline[(i + 1) % count]);
+            if !(b - a).is_zero_approx() {
+                segments.push(a);
+                segments.push(b);
             }
-            set![ x => 0b01 ];
-            set![ y => 0b10 ];
+        }
+        segments
+    }
 
-            match mask {
-                // over one of the six faces
-                0b01 => Vector2::new(position.x.sign(), 0.0),
-                0b10 => Vector2::new(0.0, position.y.sign()),
+    /// Primitives of a rounded rectangle, described before the shapes are created
+    pub(super) struct RoundedLayout {
+        /// Size of the rectangle filling the inside.
+        /// Should be None if the cuboid is hollow.
+        pub face: Option<Vector2>,
 
-                // over one of the eight corners
-                0b11 => (self.box_size * position.sign()).direction_to(*position),
+        /// Radius of the capsules rounding the edges
+        pub radius: real,
 
-                // Inside of the box, should not happen
-                _ => position.normalized_or_zero(),
-            }
+        /// Height of the capsules for each pair of parallel edges and their transforms
+        pub edges: [(real, TransformBuilder2D<1, 2>); 2],
+    }
+
+    /// Lay out the primitives of a rounded rectangle.
+    /// Unlike in 3D the face does not need to grow, the capsules
+    /// already cover the band of the outline along each side.
+    pub(super) fn rounded_layout(size: &Vector2, radius: real, hollow: bool) -> RoundedLayout {
+        let diameter = radius * 2.0;
+        let half = *size * 0.5;
+        macro_rules! pos {
+            ( $x:tt , $y:tt ) => {
+                Vector2::new(half.x * unit![$x], half.y * unit![$y])
+            };
+        }
+
+        // prepare the transforms for the four edges
+        let edges = [
+            (
+                size.x + diameter,
+                TransformBuilder2D::new([ROT_X], [pos![ 0, + ], pos![ 0, - ]]),
+            ),
+            (
+                size.y + diameter,
+                TransformBuilder2D::new([ROT_Y], [pos![ +, 0 ], pos![ -, 0 ]]),
+            ),
+        ];
+        RoundedLayout {
+            face: (!hollow).then_some(*size),
+            radius,
+            edges,
         }
     }
 
     /// Specify if we need to generate a single box shape or
     /// if we need multiple one to create the rounded edges.
+    #[derive(Clone)]
     enum Internal {
         /// No rounding, we only need a single box shape.
         Simple(Gd<RectangleShape2D>),
 
         /// With rounding we need multiple boxes and capsules.
         Rounded {
-            /// One box for each of the three side of the box.
+            /// A single box filling the inside of the rectangle.
             /// Should be None if the cuboid is hollow.
             face: Option<Gd<RectangleShape2D>>,
 
             /// Capsules for each set of four parallel edges.
             edges: Box<[(Gd<CapsuleShape2D>, TransformBuilder2D<1, 2>); 2]>,
         },
+
+        /// A single concave shape following the outline of the rounded rectangle.
+        Trimesh(Gd<ConcavePolygonShape2D>),
     }
 
     impl Internal {
@@ -180,49 +431,31 @@ pub mod inner2d {
 
         /// Create a rounded box shape
         fn new_rounded(size: &Vector2, radius: real, hollow: bool) -> Self {
-            // Create a shape
-            let diameter = radius * 2.0;
-            macro_rules! edge {
-                ( $coord:ident ) => {{
-                    let mut edge = CapsuleShape2D::new_gd();
-                    edge.set_radius(radius);
-                    edge.set_height(size.$coord + diameter);
-                    edge
-                }};
-            }
-            macro_rules! pos {
-                ( $x:tt , $y:tt ) => {
-                    Vector2::new(size.x * unit![$x], size.y * unit![$y])
-                };
-            }
-
-            // create the faces
-            let face = if hollow {
-                None
-            } else {
+            let layout = rounded_layout(size, radius, hollow);
+            let face = layout.face.map(|size| {
                 let mut face = RectangleShape2D::new_gd();
-
-                face.set_size(*size);
-                // Create boxes for the six faces
-                Some(face)
-            };
-
-            // prepare the transforms for the twelve edges
-            let edges = Box::new([
-                (
-                    edge![x],
-                    TransformBuilder2D::new([ROT_X], [pos![ 0, + ], pos![ 0, - ]]),
-                ),
-                (
-                    edge![y],
-                    TransformBuilder2D::new([ROT_Y], [pos![ +, 0 ], pos![ -, 0 ]]),
-                ),
-            ]);
+                face.set_size(size);
+                face
+            });
+            let edges = Box::new(layout.edges.map(|(height, trs)| {
+                let mut edge = CapsuleShape2D::new_gd();
+                edge.set_radius(layout.radius);
+                edge.set_height(height);
+                (edge, trs)
+            }));
 
             // create the rounded shape
             Self::Rounded { face, edges }
         }
 
+        /// Create a single concave shape following the outline of the rounded rectangle
+        fn new_trimesh(size: &Vector2, radius: real) -> Self {
+            let segments = PackedVector2Array::from(trimesh_segments(size, radius).as_slice());
+            let mut shape = ConcavePolygonShape2D::new_gd();
+            shape.set_segments(&segments);
+            Self::Trimesh(shape)
+        }
+
         /// Return a list of colliders
         fn colliders(&self) -> Vec<(Gd<Shape2D>, Transform2D)> {
             macro_rules! cast_shape {
@@ -235,9 +468,12 @@ pub mod inner2d {
                 Self::Simple(shape) => {
                     vec![cast_shape![shape, Transform2D::IDENTITY]]
                 }
+                Self::Trimesh(shape) => {
+                    vec![cast_shape![shape, Transform2D::IDENTITY]]
+                }
                 Self::Rounded { face, edges } => {
                     // allocate a vector to store the shapes
-                    let size = if face.is_some() { 3 * 5 } else { 3 * 4 };
+                    let size = if face.is_some() { 1 + 2 * 2 } else { 2 * 2 };
                     let mut shapes = Vec::with_capacity(size);
 
                     // Push the internal boxes into the list
@@ -247,7 +483,7 @@ pub mod inner2d {
 
                     // add the shapes for the edges
                     for (edge, trs) in edges.iter() {
-                        for i in 0..4 {
+                        for i in 0..trs.position_count() {
                             shapes.push(cast_shape![edge, trs.build(0, i)]);
                         }
                     }
@@ -261,13 +497,44 @@ pub mod inner2d {
 pub mod inner3d {
 
     use crate::{
-        gravity::{build_trs::TransformBuilder3D, field::shaped::Shape, util::util3d::*},
+        gravity::{
+            WithBounds,
+            build_trs::TransformBuilder3D,
+            field::shaped::{ColliderPrecision, Shape, TRIMESH_SEGMENTS},
+            math::math3d::cuboid_up,
+            util::util3d::*,
+        },
         unit,
     };
     use godot::{
-        classes::{BoxShape3D, CapsuleShape3D, Shape3D},
+        builtin::real_consts::FRAC_PI_2,
+        classes::{BoxShape3D, CapsuleShape3D, ConcavePolygonShape3D, Shape3D},
         prelude::*,
     };
+    use std::{
+        cell::RefCell,
+        collections::HashMap,
+        rc::{Rc, Weak},
+    };
+
+    /// Number of coordinates of the vectors
+    const DIMENSIONS: usize = 3;
+
+    /// Number of boxes of a filled rounded cuboid, the three boxes filling the faces
+    const ROUNDED_FACES: i32 = 3;
+
+    /// Number of capsules of a rounded cuboid, one capsule per edge
+    const ROUNDED_EDGES: i32 = 12;
+
+    /// Identify the size of a cuboid by the bits of its coordinates
+    #[inline]
+    fn size_key(size: &Vector3) -> [u64; DIMENSIONS] {
+        [
+            u64::from(size.x.to_bits()),
+            u64::from(size.y.to_bits()),
+            u64::from(size.z.to_bits()),
+        ]
+    }
 
     shape_cuboid! {
         GravityShapedCuboid3D where {
@@ -278,50 +545,151 @@ pub mod inner3d {
     }
 
     impl GravityShapedCuboid3D {
-        /// Pick the UP direction for a cuboid
+        /// Pick the UP direction for a cuboid, the size is halved
+        /// since the math compares it to coordinates relative to the center.
         fn up_func(&self, position: &Vector3) -> Vector3 {
-            // use a bitmask to deduce the strategy to use
-            let mut mask = 0b000;
-            macro_rules! set {
-                ( $coord:ident => $bit:literal ) => {
-                    if position.$coord.abs() > self.box_size.$coord {
-                        mask |= $bit;
-                    }
-                };
+            cuboid_up(&(self.scaled_size() * 0.5), position)
+        }
+    }
+
+    /// Triangles of the surface of a rounded box, by triplets of points
+    pub(super) fn trimesh_faces(size: &Vector3, radius: real) -> Vec<Vector3> {
+        const Q: usize = TRIMESH_SEGMENTS;
+        const SIGNS: [(real, real); 4] = [(1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0), (1.0, -1.0)];
+        let half = *size * 0.5;
+
+        // Compute the vertices as a grid of latitudes and longitudes,
+        // each hemisphere and quadrant is offset toward its corner of the box.
+        let (rows, cols) = (2 * (Q + 1), 4 * (Q + 1));
+        let mut grid = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            let (hemi, i) = (row / (Q + 1), row % (Q + 1));
+            let lat = (hemi as real + i as real / Q as real - 1.0) * FRAC_PI_2;
+            let y = if hemi == 0 { -half.y } else { half.y };
+            for (quad, (sx, sz)) in SIGNS.iter().enumerate() {
+                let corner = Vector3::new(half.x * sx, y, half.z * sz);
+                for j in 0..=Q {
+                    let lon = (quad as real + j as real / Q as real) * FRAC_PI_2;
+                    let dir = Vector3::new(lat.cos() * lon.cos(), lat.sin(), lat.cos() * lon.sin());
+                    grid.push(corner + dir * radius);
+                }
             }
-            set![ x => 0b001 ];
-            set![ y => 0b010 ];
-            set![ z => 0b100 ];
-
-            // Flatten the vector along a axis aligned plane
-            macro_rules! flatten {
-                ( $func:ident ) => {{
-                    let flat = $func(position);
-                    (self.box_size * flat.sign()).direction_to(flat)
-                }};
+        }
+
+        // Triangulate the grid, skipping the degenerated triangles
+        let mut faces = Vec::new();
+        let mut triangle = |a: Vector3, b: Vector3, c: Vector3| {
+            if !(b - a).cross(c - a).is_zero_approx() {
+                faces.push(a);
+                faces.push(b);
+                faces.push(c);
             }
-            match mask {
-                // over one of the six faces
-                0b001 => Vector3::new(position.x.sign(), 0.0, 0.0),
-                0b010 => Vector3::new(0.0, position.y.sign(), 0.0),
-                0b100 => Vector3::new(0.0, 0.0, position.z.sign()),
-
-                // over one of the twelve edges
-                0b011 => flatten![flatten_z],
-                0b101 => flatten![flatten_y],
-                0b110 => flatten![flatten_x],
-
-                // over one of the eight corners
-                0b111 => (self.box_size * position.sign()).direction_to(*position),
-
-                // Inside of the box, should not happen
-                _ => position.normalized_or_zero(),
+        };
+        for row in 0..rows - 1 {
+            for col in 0..cols {
+                let next = (col + 1) % cols;
+                let a = grid[row * cols + col];
+                let b = grid[row * cols + next];
+                let c = grid[(row + 1) * cols + col];
+                let d = grid[(row + 1) * cols + next];
+                triangle(a, c, b);
+                triangle(b, c, d);
             }
         }
+
+        // Close the bottom and top faces using the poles of each quadrant
+        for row in [0, rows - 1] {
+            let p = [0, 1, 2, 3].map(|quad| grid[row * cols + quad * (Q + 1)]);
+            triangle(p[0], p[1], p[2]);
+            triangle(p[0], p[2], p[3]);
+        }
+        faces
+    }
+
+    /// Primitives of a rounded box, described before the shapes are created
+    pub(super) struct RoundedLayout {
+        /// Size of the box filling the inside for each of the three sides.
+        /// Should be None if the cuboid is hollow.
+        pub faces: Option<[Vector3; 3]>,
+
+        /// Radius of the capsules rounding the edges
+        pub radius: real,
+
+        /// Height of the capsules for each set of four parallel edges and their transforms
+        pub edges: [(real, TransformBuilder3D<1, 4>); 3],
+    }
+
+    /// Lay out the primitives of a rounded box.
+    /// The faces grow by the diameter along their normal and the capsules span
+    /// the full edge plus their caps, so the union is the box grown by the radius.
+    pub(super) fn rounded_layout(size: &Vector3, radius: real, hollow: bool) -> RoundedLayout {
+        let diameter = radius * 2.0;
+        let half = *size * 0.5;
+        macro_rules! face {
+            ( $coord:ident ) => {{
+                let mut size = *size;
+                size.$coord += diameter;
+                size
+            }};
+        }
+        macro_rules! pos {
+            (  $x:tt , $y:tt , $z:tt ) => {
+                Vector3::new(half.x * unit![$x], half.y * unit![$y], half.z * unit![$z])
+            };
+        }
+
+        // size the boxes of the three faces
+        let faces = (!hollow).then(|| [face![x], face![y], face![z]]);
+
+        // prepare the transforms for the twelve edges
+        let edges = [
+            (
+                size.x + diameter,
+                TransformBuilder3D::new(
+                    [BASIS_X],
+                    [
+                        pos![ 0, +, + ],
+                        pos![ 0, +, - ],
+                        pos![ 0, -, - ],
+                        pos![ 0, -, + ],
+                    ],
+                ),
+            ),
+            (
+                size.y + diameter,
+                TransformBuilder3D::new(
+                    [BASIS_Y],
+                    [
+                        pos![ +, 0, + ],
+                        pos![ -, 0, + ],
+                        pos![ -, 0, - ],
+                        pos![ +, 0, - ],
+                    ],
+                ),
+            ),
+            (
+                size.z + diameter,
+                TransformBuilder3D::new(
+                    [BASIS_Z],
+                    [
+                        pos![ +, +, 0 ],
+                        pos![ -, +, 0 ],
+                        pos![ -, -, 0 ],
+                        pos![ +, -, 0 ],
+                    ],
+                ),
+            ),
+        ];
+        RoundedLayout {
+            faces,
+            radius,
+            edges,
+        }
     }
 
     /// Specify if we need to generate a single box shape or
     /// if we need multiple one to create the rounded edges.
+    #[derive(Clone)]
     enum Internal {
         /// No rounding, we only need a single box shape.
         Simple(Gd<BoxShape3D>),
@@ -335,6 +703,9 @@ pub mod inner3d {
             /// Capsules for each set of four parallel edges.
             edges: Box<[(Gd<CapsuleShape3D>, TransformBuilder3D<1, 4>); 3]>,
         },
+
+        /// A single concave shape following the surface of the rounded box.
+        Trimesh(Gd<ConcavePolygonShape3D>),
     }
 
     impl Internal {
@@ -347,83 +718,34 @@ pub mod inner3d {
 
         /// Create a rounded box shape
         fn new_rounded(size: &Vector3, radius: real, hollow: bool) -> Self {
-            // Create a shape
-            let diameter = radius * 2.0;
-            macro_rules! face {
-                ( $coord:ident ) => {{
+            let layout = rounded_layout(size, radius, hollow);
+            let faces = layout.faces.map(|sizes| {
+                sizes.map(|size| {
                     let mut face = BoxShape3D::new_gd();
-                    let mut size = *size;
-                    size.$coord += diameter;
                     face.set_size(size);
                     face
-                }};
-            }
-            macro_rules! edge {
-                ( $coord:ident ) => {{
-                    let mut edge = CapsuleShape3D::new_gd();
-                    edge.set_radius(radius);
-                    edge.set_height(size.$coord + diameter);
-                    edge
-                }};
-            }
-            macro_rules! pos {
-                (  $x:tt , $y:tt , $z:tt ) => {
-                    Vector3::new(size.x * unit![$x], size.y * unit![$y], size.z * unit![$z])
-                };
-            }
-
-            // create the faces
-            let faces = if hollow {
-                None
-            } else {
-                // Create boxes for the six faces
-                Some([face![x], face![y], face![z]])
-            };
-
-            // prepare the transforms for the twelve edges
-            let edges = Box::new([
-                (
-                    edge![x],
-                    TransformBuilder3D::new(
-                        [BASIS_X],
-                        [
-                            pos![ 0, +, + ],
-                            pos![ 0, +, - ],
-                            pos![ 0, -, - ],
-                            pos![ 0, -, + ],
-                        ],
-                    ),
-                ),
-                (
-                    edge![y],
-                    TransformBuilder3D::new(
-                        [BASIS_Y],
-                        [
-                            pos![ +, 0, + ],
-                            pos![ -, 0, + ],
-                            pos![ -, 0, - ],
-                            pos![ +, 0, - ],
-                        ],
-                    ),
-                ),
-                (
-                    edge![z],
-                    TransformBuilder3D::new(
-                        [BASIS_Z],
-                        [
-                            pos![ +, +, 0 ],
-                            pos![ -, +, 0 ],
-                            pos![ -, -, 0 ],
-                            pos![ +, -, 0 ],
-                        ],
-                    ),
-                ),
-            ]);
+                })
+            });
+            let edges = Box::new(layout.edges.map(|(height, trs)| {
+                let mut edge = CapsuleShape3D::new_gd();
+                edge.set_radius(layout.radius);
+                edge.set_height(height);
+                (edge, trs)
+            }));
 
             // create the rounded shape
             Self::Rounded { faces, edges }
         }
 
+        /// Create a single concave shape following the surface of the rounded box
+        fn new_trimesh(size: &Vector3, radius: real) -> Self {
+            let faces = PackedVector3Array::from(trimesh_faces(size, radius).as_slice());
+            let mut shape = ConcavePolygonShape3D::new_gd();
+            shape.set_faces(&faces);
+            shape.set_backface_collision_enabled(true);
+            Self::Trimesh(shape)
+        }
+
         /// Return a list of colliders
         fn colliders(&self) -> Vec<(Gd<Shape3D>, Transform3D)> {
             macro_rules! cast_shape {
@@ -436,6 +758,9 @@ pub mod inner3d {
                 Self::Simple(shape) => {
                     vec![cast_shape![shape, Transform3D::IDENTITY]]
                 }
+                Self::Trimesh(shape) => {
+                    vec![cast_shape![shape, Transform3D::IDENTITY]]
+                }
                 Self::Rounded { faces, edges } => {
                     // allocate a vector to store the shapes
                     let size = if faces.is_some() { 3 * 5 } else { 3 * 4 };
@@ -450,7 +775,7 @@ pub mod inner3d {
 
                     // add the shapes for the edges
                     for (edge, trs) in edges.iter() {
-                        for i in 0..4 {
+                        for i in 0..trs.position_count() {
                             shapes.push(cast_shape![edge, trs.build(0, i)]);
                         }
                     }
@@ -460,3 +785,130 @@ pub mod inner3d {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        inner2d::{self, trimesh_segments},
+        inner3d::{collider_half_extents, rounded_layout, trimesh_faces},
+    };
+    use crate::gravity::math::math3d::cuboid_up;
+    use godot::builtin::{Aabb, Transform2D, Transform3D, Vector2, Vector3, math::ApproxEq, real};
+
+    #[test]
+    fn trimesh_outline_matches_the_primitive_bounds() {
+        // the primitives span the half size plus the edge radius on each side
+        let (size, radius) = (Vector2::new(4.0, 2.0), 0.5);
+        let points = trimesh_segments(&size, radius);
+        let max = points
+            .iter()
+            .fold(Vector2::ZERO, |acc, p| acc.coord_max(*p));
+        let min = points
+            .iter()
+            .fold(Vector2::ZERO, |acc, p| acc.coord_min(*p));
+        let half = size * 0.5 + Vector2::ONE * radius;
+        assert!(max.approx_eq(&half));
+        assert!(min.approx_eq(&-half));
+    }
+
+    #[test]
+    fn trimesh_surface_matches_the_primitive_bounds() {
+        let (size, radius) = (Vector3::new(4.0, 2.0, 6.0), 0.5);
+        let points = trimesh_faces(&size, radius);
+        assert_eq!(points.len() % 3, 0);
+        let max = points
+            .iter()
+            .fold(Vector3::ZERO, |acc, p| acc.coord_max(*p));
+        let min = points
+            .iter()
+            .fold(Vector3::ZERO, |acc, p| acc.coord_min(*p));
+        let half = size * 0.5 + Vector3::ONE * radius;
+        assert!(max.approx_eq(&half));
+        assert!(min.approx_eq(&-half));
+    }
+
+    /// Check if a point lies within the union of the rounded colliders of a rectangle
+    fn rounded_covers(layout: &inner2d::RoundedLayout, point: Vector2) -> bool {
+        const EPSILON: real = 1e-4;
+        let in_face = layout.face.is_some_and(|face| {
+            let half = face * 0.5 + Vector2::ONE * EPSILON;
+            point.x.abs() <= half.x && point.y.abs() <= half.y
+        });
+        in_face
+            || layout.edges.iter().any(|(height, trs)| {
+                let reach = Vector2::new(0.0, height * 0.5 - layout.radius);
+                (0..trs.position_count()).any(|i| {
+                    let capsule: Transform2D = trs.build(0, i);
+                    let (a, b) = (capsule * -reach, capsule * reach);
+                    let t = (point - a).dot(b - a) / (b - a).length_squared();
+                    let closest = a.lerp(b, t.clamp(0.0, 1.0));
+                    point.distance_to(closest) <= layout.radius + EPSILON
+                })
+            })
+    }
+
+    #[test]
+    fn rounded_colliders_cover_the_rounded_outline() {
+        let size = Vector2::new(4.0, 2.0);
+        for radius in [0.25, 0.5, 1.5] {
+            for hollow in [false, true] {
+                let layout = inner2d::rounded_layout(&size, radius, hollow);
+                for point in trimesh_segments(&size, radius) {
+                    assert!(
+                        rounded_covers(&layout, point),
+                        "radius {radius}, hollow {hollow}: {point:?} is not covered"
+                    );
+                }
+            }
+
+            // a filled rectangle also covers its inside, corners included
+            let layout = inner2d::rounded_layout(&size, radius, false);
+            let half = size * 0.5;
+            for point in [Vector2::ZERO, half, -half, Vector2::new(half.x, -half.y)] {
+                assert!(rounded_covers(&layout, point), "radius {radius}: {point:?}");
+            }
+        }
+    }
+
+    /// Bounding box of the rounded colliders of a cuboid, capsules lie along their Y-axis
+    fn rounded_bounds(size: &Vector3, radius: real) -> Aabb {
+        let layout = rounded_layout(size, radius, false);
+        let mut bounds = Aabb::new(Vector3::ZERO, Vector3::ZERO);
+        for face in layout.faces.expect("a filled cuboid has faces") {
+            bounds = bounds.merge(Aabb::new(-face * 0.5, face));
+        }
+        for (height, trs) in layout.edges {
+            let local = Vector3::new(radius, height * 0.5, radius);
+            for i in 0..trs.position_count() {
+                let capsule: Transform3D = trs.build(0, i);
+                bounds = bounds.merge(capsule * Aabb::new(-local, local * 2.0));
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn rounded_colliders_stay_within_bounds_for_any_radius() {
+        // radii below and beyond half of the smallest side of the box
+        let size = Vector3::new(2.0, 1.0, 3.0);
+        for radius in [0.1, 0.5, 1.0, 2.5] {
+            let half = collider_half_extents(&size, radius);
+            let bounds = rounded_bounds(&size, radius);
+            assert!(bounds.position.approx_eq(&-half), "radius {radius}");
+            assert!(bounds.size.approx_eq(&(half * 2.0)), "radius {radius}");
+
+            // the gravity stays defined from the center up to the corners of the bounds
+            for position in [
+                Vector3::ZERO,
+                half,
+                -half,
+                Vector3::new(half.x, 0.0, -half.z),
+                Vector3::new(0.2, 0.1, 0.0),
+            ] {
+                let up = cuboid_up(&(size * 0.5), &position);
+                assert!(up.is_finite(), "radius {radius} at {position:?}");
+                assert!(up.is_normalized(), "radius {radius} at {position:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//!
+//! Spherical shell gravity field
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            math::math3d::shell_up,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity pulling toward the surface of a sphere,
+    /// outward inside of the sphere and inward outside of it.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityShell3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Radius of the sphere
+        #[export(range = (0.0, 10.0, or_greater))]
+        radius: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityShell3D => Vector3];
+
+    export_gravity_dict![GravityShell3D => [level, affects_groups, channel, radius, inverted]];
+
+    #[godot_api]
+    impl IArea3D for GravityShell3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                radius: 1.0,
+                inverted: false,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    impl Field<Vector3> for GravityShell3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction points away from the surface of the sphere
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = shell_up(position, self.radius);
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction points away from the surface of the sphere
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityShell3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::math::math3d::shell_up;
+    use godot::builtin::{Transform3D, Vector3};
+
+    #[test]
+    fn shell_radius_is_measured_from_a_moved_field() {
+        // the global position lies inside of a shell moved to X = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(11.0, 0.0, 0.0);
+        assert_eq!(shell_up(&local, 2.0), Vector3::LEFT);
+    }
+}
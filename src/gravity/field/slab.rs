@@ -0,0 +1,166 @@
+//!
+//! Slab gravity field
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            math::{math3d::slab_up, soft_cutoff},
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction, local_position},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity pulling toward a slab of finite thickness,
+    /// toward the nearest of its two faces on each side.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravitySlab3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Axis across the slab
+        #[export]
+        axis: Axis3D,
+
+        /// Half of the thickness of the slab along the axis
+        #[export(range = (0.0, 10.0, or_greater))]
+        half_thickness: real,
+
+        /// Distance from the faces beyond which the field has no strength,
+        /// zero means no limit
+        #[export(range = (0.0, 100.0, or_greater))]
+        max_distance: real,
+
+        /// Distance before the maximum over which the strength fades to zero
+        #[export(range = (0.0, 100.0, or_greater))]
+        falloff_distance: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravitySlab3D => Vector3];
+
+    export_gravity_dict![GravitySlab3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        half_thickness,
+        max_distance,
+        falloff_distance,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravitySlab3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                axis: Axis3D::Y,
+                half_thickness: 0.5,
+                max_distance: 0.0,
+                falloff_distance: 0.0,
+                inverted: false,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    impl Field<Vector3> for GravitySlab3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction points out of the nearest face of the slab
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = slab_up(position, self.axis);
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction points out of the nearest face of the slab
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+
+        /// Strength fades to zero when moving away from either face,
+        /// it is full inside of the slab.
+        fn strength(&self, position: &Vector3) -> real {
+            let local = local_position(self, position);
+            let distance = local.dot(self.axis.to_vector()).abs() - self.half_thickness;
+            soft_cutoff(distance.max(0.0), self.max_distance, self.falloff_distance)
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravitySlab3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::{axis::Axis3D, math::math3d::slab_up};
+    use godot::builtin::{Basis, Transform3D, Vector3, math::ApproxEq, real_consts::FRAC_PI_2};
+
+    #[test]
+    fn slab_picks_the_face_of_a_moved_field() {
+        // a slab moved to X = 10 and tilted a quarter turn around Z faces along X
+        let basis = Basis::from_axis_angle(Vector3::BACK, FRAC_PI_2);
+        let trs = Transform3D::new(basis, Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(9.0, 0.0, 0.0);
+        assert!((basis * slab_up(&local, Axis3D::Y)).approx_eq(&Vector3::LEFT));
+    }
+}
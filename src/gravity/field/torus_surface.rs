@@ -0,0 +1,162 @@
+//!
+//! Gravity field following the surface of a torus
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            math::math3d::torus_up,
+            util::{
+                in_any_group,
+                util3d::{closest_point_on_circle, global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity sticking objects to the surface of a torus.
+    /// No collider is generated, only the direction is computed.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityTorusSurface3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Axis going through the hole of the torus
+        #[export]
+        axis: Axis3D,
+
+        /// Distance from the center of the torus to the center of the tube
+        #[export(range = (0.0, 1.0, or_greater))]
+        ring_radius: real,
+
+        /// Radius of the tube
+        #[export(range = (0.0, 1.0, or_greater))]
+        tube_radius: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityTorusSurface3D => Vector3];
+
+    export_gravity_dict![GravityTorusSurface3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        ring_radius,
+        tube_radius,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityTorusSurface3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                axis: Axis3D::Y,
+                ring_radius: 2.0,
+                tube_radius: 1.0,
+                inverted: false,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    #[godot_api(secondary)]
+    impl GravityTorusSurface3D {
+        /// Get the signed distance from the position to the surface of the torus
+        /// in the local space of the gravity field.
+        #[func]
+        pub fn get_surface_distance(&self, position: Vector3) -> real {
+            let core = closest_point_on_circle(&position, self.axis, self.ring_radius);
+            position.distance_to(core) - self.tube_radius
+        }
+    }
+
+    impl Field<Vector3> for GravityTorusSurface3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction is the normal of the closest point on the surface of the torus,
+        /// which points away from the central circle of the tube.
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = torus_up(position, self.axis, self.ring_radius);
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction is the normal of the closest point on the surface of the torus
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityTorusSurface3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::{axis::Axis3D, math::math3d::torus_up};
+    use godot::builtin::{Transform3D, Vector3};
+
+    #[test]
+    fn torus_follows_a_moved_field() {
+        // the global position lies above the ring of a torus moved to X = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(10.0, 0.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(12.0, 1.0, 0.0);
+        assert_eq!(torus_up(&local, Axis3D::Y, 2.0), Vector3::UP);
+    }
+}
@@ -0,0 +1,145 @@
+//!
+//! Twisting gravity field
+//!
+
+pub mod inner3d {
+
+    use crate::{
+        export_gravity_dict, export_gravity_up,
+        gravity::{
+            Channel, Field, Level,
+            axis::Axis3D,
+            math::math3d::twist_up,
+            util::{
+                in_any_group,
+                util3d::{global_center, global_direction},
+            },
+        },
+    };
+    use godot::{
+        classes::{Area3D, IArea3D},
+        prelude::*,
+    };
+
+    /// Define a gravity pointing away from an axis and spiraling around it.
+    #[derive(GodotClass)]
+    #[class(base=Area3D)]
+    pub struct GravityTwist3D {
+        base: Base<Area3D>,
+
+        /// Priority level
+        #[export]
+        level: Level,
+
+        /// Groups of the nodes affected by the gravity, empty means any node
+        #[export]
+        affects_groups: Array<StringName>,
+
+        /// Gravity channel, mapped to the collision layer of the area
+        #[export(range = (0.0, 31.0))]
+        #[var(get, set = set_channel)]
+        channel: Channel,
+
+        /// Central Axis
+        #[export]
+        axis: Axis3D,
+
+        /// Rotation of the gravity around the axis in radians per unit length
+        #[export]
+        twist_rate: real,
+
+        /// Inverse the gravity
+        #[export]
+        inverted: bool,
+    }
+
+    export_gravity_up![GravityTwist3D => Vector3];
+
+    export_gravity_dict![GravityTwist3D => [
+        level,
+        affects_groups,
+        channel,
+        axis,
+        twist_rate,
+        inverted,
+    ]];
+
+    #[godot_api]
+    impl IArea3D for GravityTwist3D {
+        /// Instantiate the node
+        fn init(base: Base<Area3D>) -> Self {
+            Self {
+                base,
+                level: 0,
+                affects_groups: Array::new(),
+                channel: 0,
+                axis: Axis3D::Y,
+                twist_rate: 0.0,
+                inverted: false,
+            }
+        }
+
+        /// Forward the body signals once the node enters the tree
+        fn ready(&mut self) {
+            self.connect_gravity_signals();
+        }
+    }
+
+    impl Field<Vector3> for GravityTwist3D {
+        /// Get the priority level
+        #[inline]
+        fn level(&self) -> Level {
+            self.level
+        }
+
+        /// Up direction is the radial direction rotated around the axis
+        /// proportionally to the position along that axis.
+        fn local_up(&self, position: &Vector3) -> Vector3 {
+            let up = twist_up(position, self.axis, self.twist_rate);
+
+            // Check if the direction should be inverted
+            if self.inverted { -up } else { up }
+        }
+
+        /// Up direction is the radial direction rotated around the axis
+        fn global_up(&self, position: &Vector3) -> Vector3 {
+            global_direction(self, position)
+        }
+
+        /// Center is the origin of the area
+        #[inline]
+        fn global_center(&self) -> Vector3 {
+            global_center(self)
+        }
+
+        /// Only affect the nodes in the selected groups
+        #[inline]
+        fn affects(&self, node: &Gd<Node>) -> bool {
+            in_any_group(&self.affects_groups, node)
+        }
+
+        /// Get the gravity channel
+        #[inline]
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+}
+
+// re-export types
+pub use inner3d::GravityTwist3D;
+
+#[cfg(test)]
+mod tests {
+    use crate::gravity::{axis::Axis3D, math::math3d::twist_up};
+    use godot::builtin::{Transform3D, Vector3, math::ApproxEq, real_consts::FRAC_PI_2};
+
+    #[test]
+    fn twist_follows_a_moved_field() {
+        // a quarter turn per unit along the axis of a field moved to Y = 10
+        let trs = Transform3D::IDENTITY.translated(Vector3::new(0.0, 10.0, 0.0));
+        let local = trs.affine_inverse() * Vector3::new(2.0, 11.0, 0.0);
+        let up = twist_up(&local, Axis3D::Y, FRAC_PI_2);
+        assert!(up.approx_eq(&Vector3::RIGHT.rotated(Vector3::UP, FRAC_PI_2)));
+    }
+}
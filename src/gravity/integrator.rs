@@ -0,0 +1,251 @@
+//!
+//! Helper node integrating gravity in a fixed substep loop
+//!
+
+use crate::gravity::{math::soft_cutoff, query::GravityQuery3D};
+use godot::{classes::PhysicsDirectSpaceState3D, prelude::*};
+use std::ops::DerefMut;
+
+/// Integrate the motion of a body under gravity over several substeps,
+/// which keeps fast bodies crossing fields within a frame accurate.
+#[derive(GodotClass)]
+#[class(base=Node)]
+pub struct GravityIntegrator3D {
+    base: Base<Node>,
+
+    /// Query used to find the gravity direction
+    #[export]
+    query: Option<Gd<GravityQuery3D>>,
+
+    /// Magnitude of the gravity acceleration
+    #[export]
+    gravity: real,
+
+    /// Number of integration substeps per call
+    #[export(range = (1.0, 8.0, or_greater))]
+    substeps: u32,
+
+    /// Query the gravity again at each substep instead of once per call.
+    /// Each substep then costs a full physics query.
+    #[export]
+    requery_per_substep: bool,
+
+    /// Project the gravity onto the plane perpendicular to the velocity,
+    /// so the body sticks to surfaces without being pulled against its motion.
+    #[export]
+    stick_to_surface: bool,
+
+    /// Scale of the projected gravity while sticking to surfaces
+    #[export(range = (0.0, 2.0, or_greater))]
+    stick_strength: real,
+
+    /// Speed beyond which the gravity is off, zero means no threshold
+    #[export(range = (0.0, 100.0, or_greater))]
+    speed_threshold: real,
+
+    /// Speed before the threshold over which the gravity fades out
+    #[export(range = (0.0, 100.0, or_greater))]
+    speed_falloff: real,
+}
+
+#[godot_api]
+impl INode for GravityIntegrator3D {
+    /// Instantiate the node
+    fn init(base: Base<Node>) -> Self {
+        Self {
+            base,
+            query: None,
+            gravity: 9.8,
+            substeps: 4,
+            requery_per_substep: false,
+            stick_to_surface: false,
+            stick_strength: 1.0,
+            speed_threshold: 0.0,
+            speed_falloff: 0.0,
+        }
+    }
+}
+
+#[godot_api]
+impl GravityIntegrator3D {
+    /// Integrate the motion over `delta` seconds.
+    /// Return a dictionary with the new `position` and `velocity`.
+    #[func]
+    pub fn integrate(
+        &self,
+        mut space: Gd<PhysicsDirectSpaceState3D>,
+        position: Vector3,
+        velocity: Vector3,
+        delta: real,
+    ) -> Dictionary {
+        let (position, velocity) =
+            self.integrate_motion(space.deref_mut(), &position, &velocity, delta);
+        vdict! {
+            "position": position,
+            "velocity": velocity
+        }
+    }
+}
+
+impl GravityIntegrator3D {
+    /// Integrate the motion over `delta` seconds using semi-implicit Euler steps.
+    /// Return the new position and velocity.
+    pub fn integrate_motion(
+        &self,
+        space: &mut PhysicsDirectSpaceState3D,
+        position: &Vector3,
+        velocity: &Vector3,
+        delta: real,
+    ) -> (Vector3, Vector3) {
+        let Some(query) = self.query.as_ref() else {
+            return (*position + *velocity * delta, *velocity);
+        };
+        let query = query.bind();
+        let up_at = |space: &mut PhysicsDirectSpaceState3D, position: &Vector3| {
+            query
+                .gravity_direction(space, position)
+                .map_or(Vector3::ZERO, |(up, _)| up)
+        };
+
+        integrate_substeps(
+            position,
+            velocity,
+            delta,
+            self.substeps,
+            self.requery_per_substep,
+            |position| up_at(space, position),
+            |up, velocity| self.acceleration(up, velocity),
+        )
+    }
+
+    /// Acceleration of the gravity along the UP direction for a body at the given velocity
+    fn acceleration(&self, up: &Vector3, velocity: &Vector3) -> Vector3 {
+        let scale = speed_scale(velocity.length(), self.speed_threshold, self.speed_falloff);
+        let gravity = -*up * (self.gravity * scale);
+        if self.stick_to_surface {
+            stick_to_surface(gravity, velocity, self.stick_strength)
+        } else {
+            gravity
+        }
+    }
+}
+
+/// Scale of the gravity for a body at the given speed, full below the threshold
+/// minus the falloff, fading to zero at the threshold. A zero threshold keeps it full.
+#[inline]
+fn speed_scale(speed: real, threshold: real, falloff: real) -> real {
+    soft_cutoff(speed, threshold, falloff)
+}
+
+/// Project the gravity onto the plane perpendicular to the velocity and scale it
+/// by the strength. The gravity of a body at rest is only scaled.
+fn stick_to_surface(gravity: Vector3, velocity: &Vector3, strength: real) -> Vector3 {
+    let direction = velocity.normalized_or_zero();
+    (gravity - direction * gravity.dot(direction)) * strength
+}
+
+/// Integrate the motion over `delta` seconds using semi-implicit Euler steps,
+/// `up_at` gives the UP direction at a position, either once or at each substep,
+/// and `acceleration` the acceleration along it for the current velocity.
+fn integrate_substeps(
+    position: &Vector3,
+    velocity: &Vector3,
+    delta: real,
+    substeps: u32,
+    requery_per_substep: bool,
+    mut up_at: impl FnMut(&Vector3) -> Vector3,
+    acceleration: impl Fn(&Vector3, &Vector3) -> Vector3,
+) -> (Vector3, Vector3) {
+    let substeps = substeps.max(1);
+    let step = delta / substeps as real;
+    let (mut position, mut velocity) = (*position, *velocity);
+    let mut up = up_at(&position);
+    for i in 0..substeps {
+        if requery_per_substep && i > 0 {
+            up = up_at(&position);
+        }
+        velocity += acceleration(&up, &velocity) * step;
+        position += velocity * step;
+    }
+    (position, velocity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{integrate_substeps, speed_scale, stick_to_surface};
+    use godot::builtin::{Vector3, math::ApproxEq};
+
+    /// Constant gravity of magnitude 8 along the UP direction
+    fn pull(up: &Vector3, _velocity: &Vector3) -> Vector3 {
+        -*up * 8.0
+    }
+
+    /// Gravity pulls down before the boundary at X = 0 and forward beyond it
+    fn up_at(position: &Vector3) -> Vector3 {
+        if position.x < 0.0 {
+            Vector3::UP
+        } else {
+            Vector3::BACK
+        }
+    }
+
+    #[test]
+    fn requery_follows_a_fast_body_across_a_boundary() {
+        // the body crosses the boundary after its first substep of 0.125s
+        let (start, velocity) = (Vector3::new(-0.5, 0.0, 0.0), Vector3::new(4.0, 0.0, 0.0));
+        let mut queried = Vec::new();
+        let (_, velocity) = integrate_substeps(
+            &start,
+            &velocity,
+            0.5,
+            4,
+            true,
+            |position| {
+                queried.push(position.x);
+                up_at(position)
+            },
+            pull,
+        );
+        assert_eq!(queried, [-0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(velocity, Vector3::new(4.0, -1.0, -3.0));
+    }
+
+    #[test]
+    fn single_query_keeps_the_gravity_of_the_start() {
+        let (start, velocity) = (Vector3::new(-0.5, 0.0, 0.0), Vector3::new(4.0, 0.0, 0.0));
+        let (_, velocity) = integrate_substeps(&start, &velocity, 0.5, 4, false, up_at, pull);
+        assert_eq!(velocity, Vector3::new(4.0, -4.0, 0.0));
+    }
+
+    #[test]
+    fn sticking_projects_the_gravity_out_of_the_velocity() {
+        // running along a wall, the pull toward the wall stays and the pull along the run goes
+        let gravity = Vector3::new(0.0, -6.0, -8.0);
+        let velocity = Vector3::new(0.0, -3.0, 0.0);
+        let stuck = stick_to_surface(gravity, &velocity, 1.0);
+        assert!(stuck.approx_eq(&Vector3::new(0.0, 0.0, -8.0)));
+        assert!(stuck.dot(velocity).abs() < 1e-5);
+        assert!(stick_to_surface(gravity, &velocity, 0.5).approx_eq(&(stuck * 0.5)));
+    }
+
+    #[test]
+    fn sticking_keeps_the_gravity_of_a_body_at_rest() {
+        let gravity = Vector3::new(0.0, -9.8, 0.0);
+        assert_eq!(stick_to_surface(gravity, &Vector3::ZERO, 1.0), gravity);
+    }
+
+    #[test]
+    fn gravity_fades_out_across_the_speed_threshold() {
+        // full up to 8, fading out over the last 2 before a threshold of 10
+        assert_eq!(speed_scale(0.0, 10.0, 2.0), 1.0);
+        assert_eq!(speed_scale(8.0, 10.0, 2.0), 1.0);
+        assert_eq!(speed_scale(9.0, 10.0, 2.0), 0.5);
+        assert_eq!(speed_scale(10.0, 10.0, 2.0), 0.0);
+        assert_eq!(speed_scale(20.0, 10.0, 2.0), 0.0);
+
+        // a sharp cut without falloff, no cut without threshold
+        assert_eq!(speed_scale(9.9, 10.0, 0.0), 1.0);
+        assert_eq!(speed_scale(10.1, 10.0, 0.0), 0.0);
+        assert_eq!(speed_scale(100.0, 0.0, 2.0), 1.0);
+    }
+}
@@ -0,0 +1,568 @@
+//!
+//! Pure math core of the gravity fields and shapes,
+//! usable without instantiating any Godot object.
+//!
+
+/// Side of the face nearest to a coordinate inside of a box,
+/// the center is attributed to the positive side.
+#[inline]
+fn face_sign(coord: godot::builtin::real) -> godot::builtin::real {
+    if coord < 0.0 { -1.0 } else { 1.0 }
+}
+
+/// Smooth Hermite interpolation of a ratio clamped between zero and one
+#[inline]
+fn smoothstep(ratio: godot::builtin::real) -> godot::builtin::real {
+    let ratio = ratio.clamp(0.0, 1.0);
+    ratio * ratio * (3.0 - 2.0 * ratio)
+}
+
+/// Ratio ramping linearly from one to zero over the last `falloff_distance`
+/// before `max_distance`, and zero beyond. A non-positive maximum distance means no limit.
+pub fn soft_cutoff(
+    distance: godot::builtin::real,
+    max_distance: godot::builtin::real,
+    falloff_distance: godot::builtin::real,
+) -> godot::builtin::real {
+    if max_distance <= 0.0 {
+        1.0
+    } else if distance >= max_distance {
+        0.0
+    } else if falloff_distance <= 0.0 {
+        1.0
+    } else {
+        ((max_distance - distance) / falloff_distance).min(1.0)
+    }
+}
+
+pub mod math2d {
+
+    use super::{face_sign, smoothstep};
+    use crate::gravity::{axis::Axis2D, util::util2d::flatten};
+    use godot::builtin::{math::FloatExt, *};
+
+    /// Pick the UP direction for a rectangle centered on the origin.
+    /// The half size is half of the full `box_size` of the cuboid shape.
+    pub fn cuboid_up(half_size: &Vector2, position: &Vector2) -> Vector2 {
+        // use a bitmask to deduce the strategy to use
+        let mut mask = 0b00;
+        macro_rules! set {
+            ( $coord:ident => $bit:literal ) => {
+                if position.$coord.abs() > half_size.$coord {
+                    mask |= $bit;
+                }
+            };
+        }
+        set![ x => 0b01 ];
+        set![ y => 0b10 ];
+
+        match mask {
+            // over one of the six faces
+            0b01 => Vector2::new(position.x.sign(), 0.0),
+            0b10 => Vector2::new(0.0, position.y.sign()),
+
+            // over one of the eight corners
+            0b11 => (*half_size * position.sign()).direction_to(*position),
+
+            // Inside of the box, point away from the nearest face
+            _ => {
+                let depth = *half_size - position.abs();
+                if depth.x <= depth.y {
+                    Vector2::new(face_sign(position.x), 0.0)
+                } else {
+                    Vector2::new(0.0, face_sign(position.y))
+                }
+            }
+        }
+    }
+
+    /// Pick the UP direction normal to the side of a cone standing on the axis,
+    /// tilted between the radial direction and the axis by its height and radius.
+    /// A non-zero smoothing bends the direction toward the axis inside the cone and
+    /// toward the radial direction outside of it, over a band proportional to its slant.
+    pub fn cone_up(
+        position: &Vector2,
+        axis: Axis2D,
+        height: real,
+        radius: real,
+        smoothing: real,
+    ) -> Vector2 {
+        let axial = axis.to_vector();
+        let radial = flatten(position, axis).normalized_or_zero();
+        let normal = (radial * height + axial * radius).normalized_or_zero();
+
+        let slant = height.hypot(radius);
+        let band = smoothing.clamp(0.0, 1.0) * slant;
+        if band <= 0.0 || normal.is_zero_approx() {
+            return normal;
+        }
+
+        // signed distance to the side of the cone, negative inside
+        let distance = (*position - radial * radius).dot(normal);
+        let (target, distance) = if distance < 0.0 {
+            (axial, -distance)
+        } else {
+            (radial, distance)
+        };
+        normal
+            .lerp(target, smoothstep(distance / band))
+            .normalized_or_zero()
+    }
+
+    /// Pick the UP direction pointing away from the nearest point of the central
+    /// segment of a capsule centered on the origin along the axis. As for capsule
+    /// shapes, the height is the full height of the capsule, caps included.
+    pub fn capsule_up(position: &Vector2, axis: Axis2D, height: real, radius: real) -> Vector2 {
+        let axis_vector = axis.to_vector();
+        let half_segment = (height * 0.5 - radius).max(0.0);
+        let along = position.dot(axis_vector).clamp(-half_segment, half_segment);
+        (*position - axis_vector * along).normalized_or_zero()
+    }
+}
+
+pub mod math3d {
+
+    use super::{face_sign, smoothstep};
+    use crate::gravity::{
+        axis::Axis3D,
+        util::util3d::{closest_point_on_circle, flatten, flatten_x, flatten_y, flatten_z},
+    };
+    use godot::builtin::{math::FloatExt, *};
+
+    /// Pick the UP direction for a cuboid centered on the origin.
+    /// The half size is half of the full `box_size` of the cuboid shape.
+    pub fn cuboid_up(half_size: &Vector3, position: &Vector3) -> Vector3 {
+        // use a bitmask to deduce the strategy to use
+        let mut mask = 0b000;
+        macro_rules! set {
+            ( $coord:ident => $bit:literal ) => {
+                if position.$coord.abs() > half_size.$coord {
+                    mask |= $bit;
+                }
+            };
+        }
+        set![ x => 0b001 ];
+        set![ y => 0b010 ];
+        set![ z => 0b100 ];
+
+        // Flatten the vector along a axis aligned plane
+        macro_rules! flatten {
+            ( $func:ident ) => {{
+                let flat = $func(position);
+                (*half_size * flat.sign()).direction_to(flat)
+            }};
+        }
+        match mask {
+            // over one of the six faces
+            0b001 => Vector3::new(position.x.sign(), 0.0, 0.0),
+            0b010 => Vector3::new(0.0, position.y.sign(), 0.0),
+            0b100 => Vector3::new(0.0, 0.0, position.z.sign()),
+
+            // over one of the twelve edges
+            0b011 => flatten![flatten_z],
+            0b101 => flatten![flatten_y],
+            0b110 => flatten![flatten_x],
+
+            // over one of the eight corners
+            0b111 => (*half_size * position.sign()).direction_to(*position),
+
+            // Inside of the box, point away from the nearest face
+            _ => {
+                let depth = *half_size - position.abs();
+                if depth.x <= depth.y && depth.x <= depth.z {
+                    Vector3::new(face_sign(position.x), 0.0, 0.0)
+                } else if depth.y <= depth.z {
+                    Vector3::new(0.0, face_sign(position.y), 0.0)
+                } else {
+                    Vector3::new(0.0, 0.0, face_sign(position.z))
+                }
+            }
+        }
+    }
+
+    /// Pick the UP direction pointing away from the axis and rotated
+    /// around it proportionally to the position along that axis.
+    pub fn twist_up(position: &Vector3, axis: Axis3D, twist_rate: real) -> Vector3 {
+        let axis_vector = axis.to_vector();
+        let radial = flatten(position, axis).normalized_or_zero();
+        if radial.is_zero_approx() {
+            radial
+        } else {
+            radial.rotated(axis_vector, twist_rate * position.dot(axis_vector))
+        }
+    }
+
+    /// Pick the UP direction pointing away from the central circle of a torus
+    pub fn torus_up(position: &Vector3, axis: Axis3D, ring_radius: real) -> Vector3 {
+        let core = closest_point_on_circle(position, axis, ring_radius);
+        (*position - core).normalized_or_zero()
+    }
+
+    /// Pick the UP direction normal to the side of a cone standing on the axis,
+    /// tilted between the radial direction and the axis by its height and radius.
+    /// A non-zero smoothing bends the direction toward the axis inside the cone and
+    /// toward the radial direction outside of it, over a band proportional to its slant.
+    pub fn cone_up(
+        position: &Vector3,
+        axis: Axis3D,
+        height: real,
+        radius: real,
+        smoothing: real,
+    ) -> Vector3 {
+        let axial = axis.to_vector();
+        let radial = flatten(position, axis).normalized_or_zero();
+        let normal = (radial * height + axial * radius).normalized_or_zero();
+
+        let slant = height.hypot(radius);
+        let band = smoothing.clamp(0.0, 1.0) * slant;
+        if band <= 0.0 || normal.is_zero_approx() {
+            return normal;
+        }
+
+        // signed distance to the side of the cone, negative inside
+        let distance = (*position - radial * radius).dot(normal);
+        let (target, distance) = if distance < 0.0 {
+            (axial, -distance)
+        } else {
+            (radial, distance)
+        };
+        normal
+            .lerp(target, smoothstep(distance / band))
+            .normalized_or_zero()
+    }
+
+    /// Pick the UP direction pointing away from the surface of a sphere,
+    /// inward inside of the sphere and outward outside of it.
+    pub fn shell_up(position: &Vector3, radius: real) -> Vector3 {
+        position.normalized_or_zero() * (position.length() - radius).sign()
+    }
+
+    /// Pick the UP direction pointing out of the nearest of the two faces of a slab
+    /// centered on the origin across the axis, inside of the slab as well as outside.
+    #[inline]
+    pub fn slab_up(position: &Vector3, axis: Axis3D) -> Vector3 {
+        let axis_vector = axis.to_vector();
+        axis_vector * face_sign(position.dot(axis_vector))
+    }
+
+    /// Velocity of a point rotating around the axis at the given angular speed
+    #[inline]
+    pub fn spin_velocity(position: &Vector3, axis: Axis3D, spin: real) -> Vector3 {
+        (axis.to_vector() * spin).cross(flatten(position, axis))
+    }
+
+    /// Pick the UP direction for a solid cylinder centered on the origin along the axis.
+    /// Point away from the axis between the caps, along the axis beyond the caps
+    /// and away from the rim of the caps otherwise.
+    pub fn cylinder_up(position: &Vector3, axis: Axis3D, radius: real, height: real) -> Vector3 {
+        let axis_vector = axis.to_vector();
+        let along = position.dot(axis_vector);
+        let flat = flatten(position, axis);
+        let half_height = height * 0.5;
+
+        if along.abs() <= half_height {
+            // between the caps
+            flat.normalized_or_zero()
+        } else if flat.length() <= radius {
+            // beyond one of the caps
+            axis_vector * along.sign()
+        } else {
+            // beyond the rim of one of the caps
+            let rim = flat.normalized() * radius + axis_vector * (half_height * along.sign());
+            rim.direction_to(*position)
+        }
+    }
+
+    /// Pick the UP direction for a square frustum standing on the axis, its base
+    /// centered on the origin. The direction is the outward normal of the face,
+    /// among the four sloped sides and the two caps, farthest from the position.
+    /// Inside of the frustum this is the nearest face.
+    pub fn pyramid_up(
+        position: &Vector3,
+        axis: Axis3D,
+        base_size: &Vector2,
+        top_size: &Vector2,
+        height: real,
+    ) -> Vector3 {
+        let axial = axis.to_vector();
+        let (side_x, side_y) = match axis {
+            Axis3D::X | Axis3D::NegX => (Vector3::UP, Vector3::BACK),
+            Axis3D::Y | Axis3D::NegY => (Vector3::RIGHT, Vector3::BACK),
+            Axis3D::Z | Axis3D::NegZ => (Vector3::RIGHT, Vector3::UP),
+        };
+        let along = position.dot(axial);
+        let base_half = *base_size * 0.5;
+        let top_half = *top_size * 0.5;
+
+        // start with the caps
+        let (mut up, mut distance) = if along - height > -along {
+            (axial, along - height)
+        } else {
+            (-axial, -along)
+        };
+
+        // then check the four sloped sides
+        for (side, base, top) in [
+            (side_x, base_half.x, top_half.x),
+            (-side_x, base_half.x, top_half.x),
+            (side_y, base_half.y, top_half.y),
+            (-side_y, base_half.y, top_half.y),
+        ] {
+            let normal = (side * height + axial * (base - top)).normalized_or_zero();
+            let side_distance = (*position - side * base).dot(normal);
+            if side_distance > distance {
+                up = normal;
+                distance = side_distance;
+            }
+        }
+        up
+    }
+
+    /// Pick the UP direction pointing away from the nearest point of the central
+    /// segment of a capsule centered on the origin along the axis. As for capsule
+    /// shapes, the height is the full height of the capsule, caps included.
+    pub fn capsule_up(position: &Vector3, axis: Axis3D, height: real, radius: real) -> Vector3 {
+        let axis_vector = axis.to_vector();
+        let half_segment = (height * 0.5 - radius).max(0.0);
+        let along = position.dot(axis_vector).clamp(-half_segment, half_segment);
+        (*position - axis_vector * along).normalized_or_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{math2d, math3d::*, soft_cutoff};
+    use crate::gravity::axis::{Axis2D, Axis3D};
+    use godot::builtin::{Vector2, Vector3, math::ApproxEq};
+
+    /// Assert two vectors are approximately equal
+    #[track_caller]
+    fn assert_approx(actual: Vector3, expected: Vector3) {
+        assert!(
+            actual.approx_eq(&expected),
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn cuboid_up_over_face_edge_and_corner() {
+        let half = Vector3::ONE;
+        assert_approx(
+            cuboid_up(&half, &Vector3::new(3.0, 0.5, 0.0)),
+            Vector3::RIGHT,
+        );
+        assert_approx(
+            cuboid_up(&half, &Vector3::new(0.0, -2.0, 0.9)),
+            Vector3::DOWN,
+        );
+        assert_approx(
+            cuboid_up(&half, &Vector3::new(2.0, 2.0, 0.0)),
+            Vector3::new(1.0, 1.0, 0.0).normalized(),
+        );
+        assert_approx(
+            cuboid_up(&half, &Vector3::new(-2.0, 2.0, 2.0)),
+            Vector3::new(-1.0, 1.0, 1.0).normalized(),
+        );
+    }
+
+    #[test]
+    fn cuboid_up_inside_points_out_of_nearest_face() {
+        let half = Vector3::ONE;
+        assert_approx(
+            cuboid_up(&half, &Vector3::new(0.9, 0.0, 0.0)),
+            Vector3::RIGHT,
+        );
+        assert_approx(
+            cuboid_up(&half, &Vector3::new(0.1, -0.8, 0.2)),
+            Vector3::DOWN,
+        );
+    }
+
+    #[test]
+    fn cone_up_without_smoothing_is_the_side_normal() {
+        let normal = Vector3::new(1.0, 1.0, 0.0).normalized();
+        for position in [Vector3::new(2.0, 0.0, 0.0), Vector3::new(0.2, 0.5, 0.0)] {
+            assert_approx(cone_up(&position, Axis3D::Y, 1.0, 1.0, 0.0), normal);
+        }
+    }
+
+    #[test]
+    fn cone_up_with_smoothing_bends_away_from_the_side() {
+        let normal = Vector3::new(1.0, 1.0, 0.0).normalized();
+
+        // on the side of the cone the direction is still the normal
+        let side = cone_up(&Vector3::RIGHT, Axis3D::Y, 1.0, 1.0, 1.0);
+        assert_approx(side, normal);
+
+        // outside of the cone it bends toward the radial direction
+        let outside = cone_up(&Vector3::new(2.0, 0.0, 0.0), Axis3D::Y, 1.0, 1.0, 1.0);
+        assert!(outside.is_normalized());
+        assert!(outside.x > outside.y);
+
+        // inside of the cone it bends toward the axis
+        let inside = cone_up(&Vector3::new(0.2, 0.0, 0.0), Axis3D::Y, 1.0, 1.0, 1.0);
+        assert!(inside.is_normalized());
+        assert!(inside.y > inside.x);
+    }
+
+    #[test]
+    fn cylinder_up_between_beyond_and_around_the_caps() {
+        let up = |x, y| cylinder_up(&Vector3::new(x, y, 0.0), Axis3D::Y, 1.0, 2.0);
+        assert_approx(up(2.0, 0.0), Vector3::RIGHT);
+        assert_approx(up(0.5, 3.0), Vector3::UP);
+        assert_approx(up(0.5, -3.0), Vector3::DOWN);
+        assert_approx(up(2.0, 2.0), Vector3::new(1.0, 1.0, 0.0).normalized());
+    }
+
+    #[test]
+    fn pyramid_up_picks_the_farthest_face() {
+        let size = Vector2::new(2.0, 2.0);
+        let up = |x, y| pyramid_up(&Vector3::new(x, y, 0.0), Axis3D::Y, &size, &size, 2.0);
+        assert_approx(up(0.0, 3.0), Vector3::UP);
+        assert_approx(up(0.0, -1.0), Vector3::DOWN);
+        assert_approx(up(3.0, 1.0), Vector3::RIGHT);
+        assert_approx(up(0.9, 1.0), Vector3::RIGHT);
+    }
+
+    #[test]
+    fn capsule_up_points_away_from_the_segment() {
+        let up = |x, y| capsule_up(&Vector3::new(x, y, 0.0), Axis3D::Y, 4.0, 1.0);
+        assert_approx(up(0.0, 3.0), Vector3::UP);
+        assert_approx(up(2.0, 0.5), Vector3::RIGHT);
+        assert_approx(up(1.0, 2.0), Vector3::new(1.0, 1.0, 0.0).normalized());
+    }
+
+    #[test]
+    fn shell_up_points_toward_the_surface() {
+        assert_approx(shell_up(&Vector3::new(1.0, 0.0, 0.0), 2.0), Vector3::LEFT);
+        assert_approx(shell_up(&Vector3::new(3.0, 0.0, 0.0), 2.0), Vector3::RIGHT);
+    }
+
+    #[test]
+    fn soft_cutoff_ramps_before_the_maximum() {
+        assert_eq!(soft_cutoff(5.0, 0.0, 1.0), 1.0);
+        assert_eq!(soft_cutoff(5.0, 10.0, 0.0), 1.0);
+        assert_eq!(soft_cutoff(7.0, 10.0, 2.0), 1.0);
+        assert_eq!(soft_cutoff(9.0, 10.0, 2.0), 0.5);
+        assert_eq!(soft_cutoff(10.0, 10.0, 2.0), 0.0);
+        assert_eq!(soft_cutoff(12.0, 10.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn spin_velocity_is_tangent_to_the_rotation() {
+        let velocity = spin_velocity(&Vector3::new(1.0, 5.0, 0.0), Axis3D::Y, 2.0);
+        assert_approx(velocity, Vector3::new(0.0, 0.0, -2.0));
+        assert_approx(
+            spin_velocity(&Vector3::new(0.0, 5.0, 0.0), Axis3D::Y, 2.0),
+            Vector3::ZERO,
+        );
+    }
+
+    /// Degenerate positions, at the center and along the axes
+    const DEGENERATE_3D: [Vector3; 5] = [
+        Vector3::ZERO,
+        Vector3::new(0.0, 2.0, 0.0),
+        Vector3::new(0.0, -2.0, 0.0),
+        Vector3::new(3.0, 0.0, 0.0),
+        Vector3::new(0.0, 0.0, -1.0),
+    ];
+
+    /// Degenerate positions, at the center and along the axes
+    const DEGENERATE_2D: [Vector2; 4] = [
+        Vector2::ZERO,
+        Vector2::new(0.0, 2.0),
+        Vector2::new(0.0, -2.0),
+        Vector2::new(3.0, 0.0),
+    ];
+
+    #[test]
+    fn degenerate_inputs_give_finite_directions_3d() {
+        let zero = Vector2::ZERO;
+        for position in &DEGENERATE_3D {
+            for up in [
+                cuboid_up(&Vector3::ZERO, position),
+                cuboid_up(&Vector3::ONE, position),
+                twist_up(position, Axis3D::Y, 1.0),
+                torus_up(position, Axis3D::Y, 0.0),
+                torus_up(position, Axis3D::Y, 1.0),
+                cone_up(position, Axis3D::Y, 0.0, 0.0, 0.0),
+                cone_up(position, Axis3D::Y, 0.0, 0.0, 1.0),
+                cone_up(position, Axis3D::Y, 1.0, 1.0, 1.0),
+                shell_up(position, 0.0),
+                slab_up(position, Axis3D::Y),
+                spin_velocity(position, Axis3D::Y, 1.0),
+                cylinder_up(position, Axis3D::Y, 0.0, 0.0),
+                pyramid_up(position, Axis3D::Y, &zero, &zero, 0.0),
+                capsule_up(position, Axis3D::Y, 0.0, 0.0),
+            ] {
+                assert!(
+                    up.x.is_finite() && up.y.is_finite() && up.z.is_finite(),
+                    "non finite direction {up:?} at {position:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn degenerate_inputs_give_finite_directions_2d() {
+        for position in &DEGENERATE_2D {
+            for up in [
+                math2d::cuboid_up(&Vector2::ZERO, position),
+                math2d::cuboid_up(&Vector2::ONE, position),
+                math2d::cone_up(position, Axis2D::Y, 0.0, 0.0, 0.0),
+                math2d::cone_up(position, Axis2D::Y, 0.0, 0.0, 1.0),
+                math2d::cone_up(position, Axis2D::Y, 1.0, 1.0, 1.0),
+                math2d::capsule_up(position, Axis2D::Y, 0.0, 0.0),
+            ] {
+                assert!(
+                    up.x.is_finite() && up.y.is_finite(),
+                    "non finite direction {up:?} at {position:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cuboid_boundary_is_at_half_the_box_size() {
+        // a cuboid shape of box size 2 spans from -1 to 1
+        let half = Vector2::new(2.0, 2.0) * 0.5;
+        let up = |x, y| math2d::cuboid_up(&half, &Vector2::new(x, y));
+
+        // right on the surface and over a face
+        assert_eq!(up(1.0, 0.5), Vector2::RIGHT);
+        assert_eq!(up(1.5, 0.5), Vector2::RIGHT);
+
+        // beyond the corner, even right past the surface
+        let diagonal = Vector2::new(1.0, 1.0).normalized();
+        assert!(up(1.001, 1.001).approx_eq(&diagonal));
+        assert!(up(1.5, 1.5).approx_eq(&diagonal));
+    }
+
+    #[test]
+    fn cuboid_up_inside_near_each_face() {
+        let half = Vector3::new(1.0, 2.0, 3.0);
+        for (position, expected) in [
+            (Vector3::new(0.9, 0.0, 0.0), Vector3::RIGHT),
+            (Vector3::new(-0.9, 0.0, 0.0), Vector3::LEFT),
+            (Vector3::new(0.0, 1.9, 0.0), Vector3::UP),
+            (Vector3::new(0.0, -1.9, 0.0), Vector3::DOWN),
+            (Vector3::new(0.0, 0.0, 2.9), Vector3::BACK),
+            (Vector3::new(0.0, 0.0, -2.9), Vector3::FORWARD),
+        ] {
+            assert_eq!(cuboid_up(&half, &position), expected);
+        }
+    }
+
+    #[test]
+    fn cuboid_up_center_ties_to_the_positive_side() {
+        assert_eq!(cuboid_up(&Vector3::ONE, &Vector3::ZERO), Vector3::RIGHT);
+        assert_eq!(
+            math2d::cuboid_up(&Vector2::ONE, &Vector2::ZERO),
+            Vector2::RIGHT
+        );
+
+        // a coordinate at zero on the nearest axis also goes to the positive side
+        let half = Vector3::new(2.0, 1.0, 2.0);
+        assert_eq!(cuboid_up(&half, &Vector3::new(0.5, 0.0, 0.5)), Vector3::UP);
+    }
+}
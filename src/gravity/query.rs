@@ -2,16 +2,282 @@
 //! Define a resource for querying the physics engine for gravity direction
 //!
 
+use crate::gravity::{Channel, Level};
+use godot::{
+    builtin::{Vector2, Vector3, real},
+    prelude::{Export, GString, GodotConvert, Var},
+};
+use std::ops::{Add, Mul};
+
+/// Select how the gravity fields sharing the highest level are blended
+#[repr(C)]
+#[derive(GodotConvert, Var, Export, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[godot(via = GString)]
+pub enum BlendMode {
+    /// Sum the UP directions of the fields scaled by their strength
+    #[default]
+    Sum,
+
+    /// Weight the UP direction of each field by the inverse of the distance
+    /// between the queried position and the center of the field.
+    DistanceWeighted,
+
+    /// Only use the field whose center is the closest to the queried position
+    Nearest,
+}
+
+/// Vector operations needed to blend the gravity of several fields
+pub(crate) trait BlendVector: Copy + Add<Output = Self> + Mul<real, Output = Self> {
+    /// Null vector
+    const ZERO: Self;
+
+    /// Length of the vector
+    fn length(self) -> real;
+
+    /// Normalized vector, or zero if the vector is null
+    fn normalized_or_zero(self) -> Self;
+
+    /// Check if the vector is approximately null
+    fn is_zero_approx(self) -> bool;
+}
+
+macro_rules! impl_blend_vector {
+    ( $( $vector:ty ),* ) => {
+        $(
+            impl BlendVector for $vector {
+                const ZERO: Self = <$vector>::ZERO;
+
+                #[inline]
+                fn length(self) -> real {
+                    <$vector>::length(self)
+                }
+
+                #[inline]
+                fn normalized_or_zero(self) -> Self {
+                    <$vector>::normalized_or_zero(self)
+                }
+
+                #[inline]
+                fn is_zero_approx(self) -> bool {
+                    <$vector>::is_zero_approx(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_blend_vector!(Vector2, Vector3);
+
+/// Blend the UP directions of the fields, given with their strength and weight.
+/// The UP direction of each field is scaled by its strength, so the fields add up
+/// like forces. Return the blended gravity and the strength of the fields, which
+/// applies when the gravity is too weak to give a direction. With the sum blend
+/// mode both are totals, otherwise the weights are normalized and both are means.
+pub(crate) fn blend_gravity<V: BlendVector>(
+    blend_mode: BlendMode,
+    fields: impl IntoIterator<Item = (V, real, real)>,
+) -> (V, real) {
+    let mut gravity = V::ZERO;
+    let mut strength = 0.0;
+    let mut total = 0.0;
+    for (up, field_strength, weight) in fields {
+        gravity = gravity + up * (field_strength * weight);
+        strength += field_strength * weight;
+        total += weight;
+    }
+
+    match blend_mode {
+        BlendMode::Sum => (gravity, strength),
+        _ if total > 0.0 => (gravity * (1.0 / total), strength / total),
+        _ => (V::ZERO, 0.0),
+    }
+}
+
+/// Average the gravity and strength of the points sampled around a position.
+/// Return `None` if no sample found any gravity field.
+pub(crate) fn average_samples<V: BlendVector>(
+    samples: impl IntoIterator<Item = (V, real)>,
+) -> Option<(V, real)> {
+    let mut found = 0;
+    let mut gravity = V::ZERO;
+    let mut strength = 0.0;
+    for (sample_gravity, sample_strength) in samples {
+        found += 1;
+        gravity = gravity + sample_gravity;
+        strength += sample_strength;
+    }
+    (found > 0).then(|| {
+        let count = found as real;
+        (gravity * (1.0 / count), strength / count)
+    })
+}
+
+/// Check if the colliders found by a physics query include a gravity field,
+/// each collider being given as the field it implements, if any.
+#[inline]
+pub(crate) fn found_field<T>(colliders: impl IntoIterator<Item = Option<T>>) -> bool {
+    colliders.into_iter().any(|field| field.is_some())
+}
+
+/// Resolve the blended gravity. If it is too weak, for instance at the singularity
+/// of a field or when fields cancel out, the rest direction is used instead and
+/// scaled by the strength of the fields.
+pub(crate) fn resolve_gravity<V: BlendVector>(
+    gravity: V,
+    strength: real,
+    rest_up: V,
+    rest_threshold: real,
+) -> V {
+    if gravity.length() <= rest_threshold || gravity.is_zero_approx() {
+        rest_up.normalized_or_zero() * strength
+    } else {
+        gravity
+    }
+}
+
+/// Gravity field found by a query, reduced to what is needed to select and blend it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Candidate<V> {
+    /// Stable identifier of the field, such as its instance id
+    pub id: i64,
+
+    /// Priority level of the field
+    pub level: Level,
+
+    /// Gravity channel of the field
+    pub channel: Channel,
+
+    /// An occluder stands between the center of the field and the queried position
+    pub occluded: bool,
+
+    /// UP direction of the field at the queried position
+    pub up: V,
+
+    /// Strength of the field at the queried position
+    pub strength: real,
+
+    /// Distance between the queried position and the center of the field
+    pub distance: real,
+}
+
+/// Settings of a query selecting the fields to blend and their weight
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Selection {
+    /// How the fields sharing the highest level are blended
+    pub blend_mode: BlendMode,
+
+    /// Range of levels below the highest one which are blended as well
+    pub priority_blend_range: Level,
+
+    /// Only keep the nearest field of the highest level
+    pub strict_priority: bool,
+
+    /// Only keep the fields of this gravity channel
+    pub channel: Option<Channel>,
+
+    /// Ratio of strength added by each field overlapping at the highest level
+    pub overlap_strength_scale: real,
+}
+
+impl Selection {
+    /// Select the fields to blend among the candidates, found in any order.
+    /// Return the index of each field selected along with its weight. The fields
+    /// are ordered by identifier, so they are always blended in the same order
+    /// and the sum does not jitter from one frame to the next.
+    pub fn select<V>(&self, candidates: &[Candidate<V>]) -> Vec<(usize, real)> {
+        let Some(level) = self.highest_level(candidates) else {
+            return Vec::new();
+        };
+
+        // strict priority only considers the highest level
+        let range = self.priority_blend_range.clamp(0, Level::MAX - 1);
+        let lowest = if self.strict_priority {
+            level
+        } else {
+            level.saturating_sub(range)
+        };
+        let mut selected: Vec<usize> = (0..candidates.len())
+            .filter(|&index| {
+                let candidate = &candidates[index];
+                self.accepts(candidate) && candidate.level >= lowest
+            })
+            .collect();
+        selected.sort_by_key(|&index| candidates[index].id);
+
+        // Weight the fields by their distance if required,
+        // then ramp the weight down with their level.
+        let ramp =
+            |field_level: Level| (range + 1 - (level - field_level)) as real / (range + 1) as real;
+        let weighted = selected.into_iter().map(|index| {
+            let candidate = &candidates[index];
+            let weight = match self.blend_mode {
+                BlendMode::Sum if !self.strict_priority => 1.0,
+                BlendMode::Sum | BlendMode::DistanceWeighted | BlendMode::Nearest => {
+                    // avoid an infinite weight at the center of the field
+                    const EPSILON: real = 1e-4;
+                    1.0 / candidate.distance.max(EPSILON)
+                }
+            };
+            (index, weight * ramp(candidate.level))
+        });
+
+        // only keep the closest field
+        if self.strict_priority || self.blend_mode == BlendMode::Nearest {
+            weighted
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(index, _)| (index, 1.0))
+                .into_iter()
+                .collect()
+        } else {
+            weighted.collect()
+        }
+    }
+
+    /// Factor applied to the strength for the fields overlapping at the highest level,
+    /// one for a single field and growing by the overlap scale for each other field.
+    pub fn overlap_factor<V>(&self, candidates: &[Candidate<V>]) -> real {
+        let Some(level) = self.highest_level(candidates) else {
+            return 1.0;
+        };
+        let overlap = candidates
+            .iter()
+            .filter(|candidate| self.accepts(candidate) && candidate.level == level)
+            .count();
+        1.0 + self.overlap_strength_scale * overlap.saturating_sub(1) as real
+    }
+
+    /// Ignore the fields hidden by an occluder
+    /// and those of other channels sharing the collision bit.
+    #[inline]
+    fn accepts<V>(&self, candidate: &Candidate<V>) -> bool {
+        !candidate.occluded
+            && self
+                .channel
+                .is_none_or(|channel| candidate.channel == channel)
+    }
+
+    /// Highest level among the fields accepted
+    #[inline]
+    fn highest_level<V>(&self, candidates: &[Candidate<V>]) -> Option<Level> {
+        candidates
+            .iter()
+            .filter(|candidate| self.accepts(candidate))
+            .map(|candidate| candidate.level)
+            .max()
+    }
+}
+
 /// Simple macro to quicly implement both gravity queries type
 macro_rules! gravity_query {
     (
         $query_type:ident where {
             [$flag:ident],
-            $parameters:ty,
+            $parameters:ty | $ray_parameters:ty,
             $space:ty,
             $dynamic_type:ident as {
                 $area:ty,
-                $vector:ty
+                $vector:ty,
+                $packed_array:ty
             }
         }
     ) => {
@@ -21,8 +287,10 @@ macro_rules! gravity_query {
         // alias provided types
         type Dynamic = $dynamic_type;
         type Parameters = $parameters;
+        type RayParameters = $ray_parameters;
         type Space = $space;
         type Vector = $vector;
+        type PackedArray = $packed_array;
 
         /// Define a gravity query object
         #[derive(GodotClass)]
@@ -33,14 +301,99 @@ macro_rules! gravity_query {
             /// Internal
             internal: Gd<Parameters>,
 
+            /// Internal parameters for occlusion raycasts
+            occlusion: Gd<RayParameters>,
+
             /// Define the collision mask
             #[export($flag)]
             #[var(get, set = set_collision_mask)]
             collision_mask: Mask,
 
+            /// Find the gravity fields registered as bodies.
+            /// Only the results implementing a gravity field are considered.
+            #[export]
+            #[var(get, set = set_collide_with_bodies)]
+            collide_with_bodies: bool,
+
+            /// Find the gravity fields registered as areas
+            #[export]
+            #[var(get, set = set_collide_with_areas)]
+            collide_with_areas: bool,
+
+            /// Only consider the gravity fields of the selected channel.
+            /// The collision mask is then replaced by the bit matching the channel
+            /// and fields of other channels sharing that bit are ignored.
+            #[export]
+            #[var(get, set = set_use_channel)]
+            use_channel: bool,
+
+            /// Gravity channel to query, channel `N` maps to the collision layer `N + 1`
+            #[export(range = (0.0, 31.0))]
+            #[var(get, set = set_channel)]
+            channel: Channel,
+
+            /// Areas ignored by the query, such as the gravity field
+            /// of the node performing the query.
+            /// Set through `set_exclude_rids` and `add_exclude`.
+            exclude: Array<Rid>,
+
+            /// Define how the fields sharing the highest level are blended
+            #[export]
+            blend_mode: BlendMode,
+
+            /// Blend the fields whose level is within this range below the highest level,
+            /// with a weight decreasing with their level. Zero only keeps the highest level.
+            #[export(range = (0.0, 10.0, or_greater))]
+            priority_blend_range: Level,
+
+            /// Only apply the field of the highest level whose center is the closest
+            /// to the position, whatever the blend mode, so ties are never blended.
+            #[export]
+            strict_priority: bool,
+
+            /// Scale the strength with the number of fields of the highest level
+            /// overlapping the position, so nesting fields makes the gravity stronger.
+            /// Each field beyond the first adds this ratio of the strength,
+            /// zero disables the scaling.
+            #[export(range = (0.0, 1.0, or_greater))]
+            overlap_strength_scale: real,
+
             /// Define the maximum number of results to report
             #[export(range = (0.0, 1.0, or_greater))]
             max_results: u32,
+
+            /// Define the collision mask of the occluders blocking gravity.
+            /// When non-zero, a ray is cast from the center of each gravity
+            /// field to the queried position, which costs one extra raycast
+            /// per field found. Fields with an occluder in between are ignored.
+            #[export($flag)]
+            #[var(get, set = set_occlusion_mask)]
+            occlusion_mask: Mask,
+
+            /// Number of points sampled around the position and averaged,
+            /// zero only samples the position itself.
+            /// Each sample costs a full physics query.
+            #[export(range = (0.0, 16.0, or_greater))]
+            sample_points: u32,
+
+            /// Distance of the sampled points from the position
+            #[export(range = (0.0, 1.0, or_greater))]
+            sample_radius: real,
+
+            /// Fallback UP direction used when the resolved gravity is too weak,
+            /// for instance at the singularity of a field or when fields cancel out.
+            /// It is scaled by the strength of the fields found.
+            #[export]
+            rest_up: Vector,
+
+            /// Magnitude under which the resolved gravity falls back to the rest direction
+            #[export(range = (0.0, 1.0, or_greater))]
+            rest_threshold: real,
+
+            /// Maximum distance between the points sampled along a ray.
+            /// Each sample costs a full physics query.
+            #[export(range = (0.01, 1.0, or_greater))]
+            ray_step: real,
         }
 
         #[godot_api]
@@ -55,64 +408,365 @@ macro_rules! gravity_query {
                 internal.set_collide_with_bodies(false);
                 internal.set_collide_with_areas(true);
 
+                // instantiate a ray parameters object for occlusion
+                let mut occlusion = RayParameters::new_gd();
+                occlusion.set_collision_mask(0);
+                occlusion.set_collide_with_bodies(true);
+                occlusion.set_collide_with_areas(false);
+
                 Self {
                     base,
                     internal,
+                    occlusion,
                     collision_mask: MASK,
+                    collide_with_bodies: false,
+                    collide_with_areas: true,
+                    use_channel: false,
+                    channel: 0,
+                    exclude: Array::new(),
+                    blend_mode: BlendMode::Sum,
+                    priority_blend_range: 0,
+                    strict_priority: false,
+                    overlap_strength_scale: 0.0,
                     max_results: 32,
+                    occlusion_mask: 0,
+                    sample_points: 0,
+                    sample_radius: 0.5,
+                    rest_up: Vector::ZERO,
+                    rest_threshold: 0.0,
+                    ray_step: 0.5,
                 }
             }
         }
 
         impl $query_type {
+            /// Find the gravity direction at the given position.
+            /// The UP direction is scaled by the strength of the gravity.
+            #[inline]
             pub fn gravity_direction(
                 &self,
                 space: &mut Space,
                 position: &Vector,
             ) -> Option<(Vector, Vec<Dynamic>)> {
-                // prepare the parameters
+                self.gravity_direction_filtered(space, position, None)
+            }
+
+            /// Find the gravity direction at the given position,
+            /// only considering the fields affecting the given node.
+            #[inline]
+            pub fn gravity_direction_for(
+                &self,
+                space: &mut Space,
+                position: &Vector,
+                node: &Gd<Node>,
+            ) -> Option<(Vector, Vec<Dynamic>)> {
+                self.gravity_direction_filtered(space, position, Some(node))
+            }
+
+            /// Find the gravity direction at each of the given positions.
+            /// The results preserve the order of the positions.
+            pub fn gravity_directions(
+                &self,
+                space: &mut Space,
+                positions: &[Vector],
+            ) -> Vec<Option<(Vector, Vec<Dynamic>)>> {
+                positions
+                    .iter()
+                    .map(|position| self.gravity_direction_filtered(space, position, None))
+                    .collect()
+            }
+
+            /// Find the gravity direction along the segment between two positions.
+            /// The segment is sampled every ray step and a result is reported
+            /// at the start and wherever the set of gravity fields changes,
+            /// as the ratio along the segment, the UP direction scaled by the
+            /// strength and the fields found, empty outside of any field.
+            pub fn gravity_along_ray(
+                &self,
+                space: &mut Space,
+                from: Vector,
+                to: Vector,
+            ) -> Vec<(real, Vector, Vec<Dynamic>)> {
+                let steps = (from.distance_to(to) / self.ray_step.max(0.01))
+                    .ceil()
+                    .max(1.0) as u32;
+
+                let mut transitions: Vec<(real, Vector, Vec<Dynamic>)> = Vec::new();
+                for step in 0..=steps {
+                    let t = step as real / steps as real;
+                    let (up, fields) = self
+                        .gravity_direction_filtered(space, &from.lerp(to, t), None)
+                        .unwrap_or_default();
+
+                    // only report the sample if the fields differ from the previous one
+                    let changed = transitions.last().is_none_or(|(_, _, previous)| {
+                        previous.len() != fields.len()
+                            || previous
+                                .iter()
+                                .zip(&fields)
+                                .any(|(a, b)| a.instance_id() != b.instance_id())
+                    });
+                    if changed {
+                        transitions.push((t, up, fields));
+                    }
+                }
+                transitions
+            }
+
+            /// Find the gravity direction, optionally filtering the fields by node
+            fn gravity_direction_filtered(
+                &self,
+                space: &mut Space,
+                position: &Vector,
+                node: Option<&Gd<Node>>,
+            ) -> Option<(Vector, Vec<Dynamic>)> {
+                if self.sample_points == 0 {
+                    return self.gravity_direction_at(space, position, node).map(
+                        |(gravity, strength, fields)| (self.resolve(gravity, strength), fields),
+                    );
+                }
+
+                // average the gravity and strength found around the position
+                let mut samples = Vec::new();
+                let mut fields: Vec<Dynamic> = Vec::new();
+                for offset in sample_offsets(self.sample_points as usize, self.sample_radius) {
+                    if let Some((sample_gravity, sample_strength, sample_fields)) =
+                        self.gravity_direction_at(space, &(*position + offset), node)
+                    {
+                        samples.push((sample_gravity, sample_strength));
+
+                        // merge the fields without duplicates
+                        for field in sample_fields {
+                            if !fields
+                                .iter()
+                                .any(|f| f.instance_id() == field.instance_id())
+                            {
+                                fields.push(field);
+                            }
+                        }
+                    }
+                }
+
+                average_samples(samples)
+                    .map(|(gravity, strength)| (self.resolve(gravity, strength), fields))
+            }
+
+            /// Use the blended gravity, or fall back to the rest direction if it is too weak
+            #[inline]
+            fn resolve(&self, gravity: Vector, strength: real) -> Vector {
+                resolve_gravity(gravity, strength, self.rest_up, self.rest_threshold)
+            }
+
+            /// Find the blended gravity and the strength of the fields at a single point
+            fn gravity_direction_at(
+                &self,
+                space: &mut Space,
+                position: &Vector,
+                node: Option<&Gd<Node>>,
+            ) -> Option<(Vector, real, Vec<Dynamic>)> {
+                self.winning_fields_at(space, position, node)
+                    .map(|(winners, overlap)| {
+                        let (fields, contributions): (Vec<_>, Vec<_>) = winners
+                            .into_iter()
+                            .map(|(area, up, strength, weight)| (area, (up, strength, weight)))
+                            .unzip();
+                        let (gravity, strength) = blend_gravity(self.blend_mode, contributions);
+                        (gravity * overlap, strength * overlap, fields)
+                    })
+            }
+
+            /// Move the shared query parameters to the given position.
+            /// Cloning the handle only bumps the reference count of the
+            /// parameters object, no new query is allocated per call.
+            #[inline]
+            fn point_parameters(&self, position: &Vector) -> Gd<Parameters> {
                 let mut params = self.internal.clone();
                 params.set_position(*position);
+                params
+            }
+
+            /// Find the gravity fields of the highest level at a single point,
+            /// along with their UP direction, strength and blending weight,
+            /// and the factor of their strength for the fields overlapping.
+            /// Return `None` if the physics query found nothing at all.
+            fn winning_fields_at(
+                &self,
+                space: &mut Space,
+                position: &Vector,
+                node: Option<&Gd<Node>>,
+            ) -> Option<(Vec<(Dynamic, Vector, real, real)>, real)> {
+                let params = self.point_parameters(position);
 
                 // perform the physics query
                 let results = space
                     .intersect_point_ex(&params)
-                    .max_results(self.max_results as i32)
+                    .max_results(self.max_results.min(i32::MAX as u32) as i32)
                     .done();
 
                 // look up the results to identify the gravity fields to use
                 if results.is_empty() {
-                    None
-                } else {
-                    // try to find the best gravity fields
-                    let mut level = Level::MIN;
-                    let mut up = Vector::ZERO;
-                    let mut fields = Vec::new();
-
-                    // check each gravity field found
-                    for result in results.iter_shared() {
-                        // Check if the point is a Gravity Field
-                        if let Ok(area) = Dynamic::try_from_variant(&result.get_or_nil("collider"))
+                    return None;
+                }
+
+                // highest level found so far, to skip the fields which cannot win anyway
+                let selection = self.selection();
+                let range = self.priority_blend_range.clamp(0, Level::MAX - 1);
+                let mut level = Level::MIN;
+                let mut areas = Vec::new();
+                let mut candidates = Vec::new();
+
+                // check each gravity field found
+                for result in results.iter_shared() {
+                    // Check if the point is a Gravity Field
+                    if let Ok(area) = Dynamic::try_from_variant(&result.get_or_nil("collider")) {
+                        // get access to the gravity field trait
+                        let field = area.dyn_bind();
+                        let field_level = field.level();
+
+                        // Ignore the field if it cannot win anyway or if it does not apply
+                        if field_level < level.saturating_sub(range)
+                            || !Self::applies(&*field, &selection, position, node)
                         {
-                            // get access to the gravity field trait
-                            let field = area.dyn_bind();
-                            let new_level = field.level();
-
-                            // Based on the level of the gravity field, either
-                            // reset the current list, simply add it or ignore it.
-                            if new_level > level {
-                                level = new_level;
-                                up = field.global_up(position);
-                                fields.clear();
-                                fields.push(area.clone());
-                            } else if new_level == level {
-                                up += field.global_up(position);
-                                fields.push(area.clone());
-                            }
+                            continue;
+                        }
+
+                        // a field hidden by an occluder does not raise the level
+                        let center = field.global_center();
+                        let occluded = self.is_occluded(space, &center, position);
+                        if !occluded {
+                            level = level.max(field_level);
                         }
+                        candidates.push(Candidate {
+                            id: area.instance_id().to_i64(),
+                            level: field_level,
+                            channel: field.channel(),
+                            occluded,
+                            up: field.global_up(position),
+                            strength: field.strength(position),
+                            distance: position.distance_to(center),
+                        });
+                        drop(field);
+                        areas.push(area);
+                    }
+                }
+
+                let winners = selection
+                    .select(&candidates)
+                    .into_iter()
+                    .map(|(index, weight)| {
+                        let candidate = &candidates[index];
+                        (
+                            areas[index].clone(),
+                            candidate.up,
+                            candidate.strength,
+                            weight,
+                        )
+                    })
+                    .collect();
+                Some((winners, selection.overlap_factor(&candidates)))
+            }
+
+            /// Check if a field found by the physics query applies at the position.
+            /// It must affect the node, belong to the selected channel
+            /// and the position must be within its bounds.
+            fn applies(
+                field: &dyn Field<Vector>,
+                selection: &Selection,
+                position: &Vector,
+                node: Option<&Gd<Node>>,
+            ) -> bool {
+                node.is_none_or(|node| field.affects(node))
+                    && selection
+                        .channel
+                        .is_none_or(|channel| field.channel() == channel)
+                    && field
+                        .bounds()
+                        .is_none_or(|bounds| bounds.contains_point(*position))
+            }
+
+            /// Settings selecting the fields to blend
+            #[inline]
+            fn selection(&self) -> Selection {
+                Selection {
+                    blend_mode: self.blend_mode,
+                    priority_blend_range: self.priority_blend_range,
+                    strict_priority: self.strict_priority,
+                    channel: self.use_channel.then_some(self.channel),
+                    overlap_strength_scale: self.overlap_strength_scale,
+                }
+            }
+
+            /// Find the gravity fields of the highest level at the given position,
+            /// with their UP direction and the weight of their contribution.
+            /// The weights depend on the blend mode and sum up to one.
+            pub fn gravity_contributions(
+                &self,
+                space: &mut Space,
+                position: &Vector,
+            ) -> Vec<(Dynamic, Vector, real)> {
+                let (winners, _) = self
+                    .winning_fields_at(space, position, None)
+                    .unwrap_or_default();
+                let total: real = winners.iter().map(|winner| winner.3).sum();
+                winners
+                    .into_iter()
+                    .map(|(area, up, _, weight)| (area, up, weight / total))
+                    .collect()
+            }
+
+            /// Check if any gravity field applies at the given position, with the same
+            /// channel and bounds filter as the gravity queries. The results are checked
+            /// in order until one applies, other colliders such as bodies are skipped.
+            /// No level resolution, occlusion nor blending is performed.
+            pub fn has_gravity_at(&self, space: &mut Space, position: &Vector) -> bool {
+                let params = self.point_parameters(position);
+                let selection = self.selection();
+
+                // perform the physics query
+                let results = space
+                    .intersect_point_ex(&params)
+                    .max_results(self.max_results.min(i32::MAX as u32) as i32)
+                    .done();
+                found_field(results.iter_shared().map(|result| {
+                    Dynamic::try_from_variant(&result.get_or_nil("collider"))
+                        .ok()
+                        .filter(|area| Self::applies(&*area.dyn_bind(), &selection, position, None))
+                }))
+            }
+
+            /// Convert the result of a gravity query into a dictionary,
+            /// empty if no gravity was found.
+            fn to_dictionary(found: Option<(Vector, Vec<Dynamic>)>) -> Dictionary {
+                if let Some((up, fields)) = found {
+                    vdict! {
+                        "up": up.normalized_or_zero().to_variant(),
+                        "strength": up.length().to_variant(),
+                        "fields": fields.to_variant()
                     }
+                } else {
+                    Dictionary::new()
+                }
+            }
+
+            /// Push the effective collision mask to the query parameters
+            fn update_collision_mask(&mut self) {
+                let mask = if self.use_channel {
+                    channel_mask(self.channel)
+                } else {
+                    self.collision_mask
+                };
+                self.internal.set_collision_mask(mask);
+            }
 
-                    Some((up.normalized_or_zero(), fields))
+            /// Check if an occluder stands between the center of a field and the position
+            fn is_occluded(&self, space: &mut Space, center: &Vector, position: &Vector) -> bool {
+                if self.occlusion_mask == 0 {
+                    false
+                } else {
+                    let mut params = self.occlusion.clone();
+                    params.set_from(*center);
+                    params.set_to(*position);
+                    !space.intersect_ray(&params).is_empty()
                 }
             }
         }
@@ -125,29 +779,190 @@ macro_rules! gravity_query {
                 mut space: Gd<Space>,
                 position: Vector,
             ) -> Dictionary {
-                if let Some((up, fields)) = self.gravity_direction(space.deref_mut(), &position) {
-                    vdict! {
-                        "up": up.to_variant(),
-                        "fields": fields.to_variant()
-                    }
-                } else {
-                    Dictionary::new()
-                }
+                Self::to_dictionary(self.gravity_direction(space.deref_mut(), &position))
+            }
+
+            /// Find the gravity direction at each of the given positions.
+            /// Return one dictionary per position in the same order,
+            /// empty where no gravity is found.
+            #[func]
+            pub fn find_gravity_directions(
+                &self,
+                mut space: Gd<Space>,
+                positions: PackedArray,
+            ) -> Array<Dictionary> {
+                self.gravity_directions(space.deref_mut(), positions.as_slice())
+                    .into_iter()
+                    .map(Self::to_dictionary)
+                    .collect()
+            }
+
+            /// Find the gravity fields of the highest level at the given position.
+            /// Return one dictionary per field with its UP direction and weight.
+            #[func]
+            pub fn find_gravity_contributions(
+                &self,
+                mut space: Gd<Space>,
+                position: Vector,
+            ) -> Array<Dictionary> {
+                self.gravity_contributions(space.deref_mut(), &position)
+                    .into_iter()
+                    .map(|(field, up, weight)| {
+                        vdict! {
+                            "field": field.to_variant(),
+                            "up": up.to_variant(),
+                            "weight": weight.to_variant()
+                        }
+                    })
+                    .collect()
+            }
+
+            /// Find the gravity along the segment between two positions.
+            /// Return one dictionary per transition between gravity fields,
+            /// with the ratio `t` along the segment where it occurs.
+            #[func]
+            pub fn find_gravity_along_ray(
+                &self,
+                mut space: Gd<Space>,
+                from: Vector,
+                to: Vector,
+            ) -> Array<Dictionary> {
+                self.gravity_along_ray(space.deref_mut(), from, to)
+                    .into_iter()
+                    .map(|(t, up, fields)| {
+                        let mut dict = Self::to_dictionary(Some((up, fields)));
+                        dict.set("t", t);
+                        dict
+                    })
+                    .collect()
+            }
+
+            #[func]
+            #[inline]
+            pub fn has_gravity(&self, mut space: Gd<Space>, position: Vector) -> bool {
+                self.has_gravity_at(space.deref_mut(), &position)
+            }
+
+            #[func]
+            pub fn find_gravity_direction_for(
+                &self,
+                mut space: Gd<Space>,
+                position: Vector,
+                node: Gd<Node>,
+            ) -> Dictionary {
+                Self::to_dictionary(self.gravity_direction_for(space.deref_mut(), &position, &node))
             }
 
             #[func]
             #[inline]
             pub fn set_collision_mask(&mut self, collision_mask: Mask) {
+                if collision_mask == 0 {
+                    godot_warn!("Gravity query with an empty collision mask finds no field");
+                }
                 self.collision_mask = collision_mask;
+                self.update_collision_mask();
+            }
+
+            /// Set the collision mask from the names of the physics layers
+            /// defined in the project settings, unknown names are ignored with a warning.
+            #[func]
+            pub fn set_collision_mask_from_layers(&mut self, names: PackedStringArray) {
+                // the layer names are stored as `layer_names/<dimension>_physics/layer_<n>`
+                let section = stringify!($flag).trim_start_matches("flags_");
+                let settings = ProjectSettings::singleton();
+                let layers: Vec<GString> = (1..=Mask::BITS)
+                    .map(|layer| {
+                        settings
+                            .get_setting(format!("layer_names/{section}/layer_{layer}").as_str())
+                            .try_to::<GString>()
+                            .unwrap_or_default()
+                    })
+                    .collect();
+
+                let mut mask = 0;
+                for name in names.as_slice() {
+                    match layers.iter().position(|layer| layer == name) {
+                        Some(bit) => mask |= 1 << bit,
+                        None => godot_warn!("Unknown physics layer \"{name}\""),
+                    }
+                }
+                self.set_collision_mask(mask);
+            }
+
+            #[func]
+            #[inline]
+            pub fn set_collide_with_bodies(&mut self, collide_with_bodies: bool) {
+                self.collide_with_bodies = collide_with_bodies;
+                self.internal.set_collide_with_bodies(collide_with_bodies);
+            }
+
+            #[func]
+            #[inline]
+            pub fn set_collide_with_areas(&mut self, collide_with_areas: bool) {
+                self.collide_with_areas = collide_with_areas;
+                self.internal.set_collide_with_areas(collide_with_areas);
+            }
+
+            #[func]
+            #[inline]
+            pub fn set_use_channel(&mut self, use_channel: bool) {
+                self.use_channel = use_channel;
+                self.update_collision_mask();
+            }
+
+            #[func]
+            #[inline]
+            pub fn set_channel(&mut self, channel: Channel) {
+                self.channel = channel.min(Mask::BITS - 1);
+                self.update_collision_mask();
+            }
+
+            /// Set the areas ignored by the query,
+            /// they are filtered out by the physics engine.
+            #[func]
+            #[inline]
+            pub fn set_exclude_rids(&mut self, rids: Array<Rid>) {
+                self.internal.set_exclude(&rids);
+                self.exclude = rids;
+            }
+
+            /// Add an area to the ones ignored by the query
+            #[func]
+            pub fn add_exclude(&mut self, rid: Rid) {
+                if !self.exclude.contains(rid) {
+                    self.exclude.push(rid);
+                    self.internal.set_exclude(&self.exclude);
+                }
+            }
+
+            /// Get the areas ignored by the query
+            #[func]
+            #[inline]
+            pub fn get_exclude_rids(&self) -> Array<Rid> {
+                self.exclude.clone()
+            }
+
+            #[func]
+            #[inline]
+            pub fn set_occlusion_mask(&mut self, occlusion_mask: Mask) {
+                self.occlusion_mask = occlusion_mask;
+                self.occlusion.set_collision_mask(occlusion_mask);
             }
         }
     };
 }
 
 pub mod inner2d {
-    use crate::gravity::{Field, Level, Mask};
+    use super::{
+        BlendMode, Candidate, Selection, average_samples, blend_gravity, found_field,
+        resolve_gravity,
+    };
+    use crate::gravity::{Channel, Field, Level, Mask, channel_mask, util::util2d::sample_offsets};
     use godot::{
-        classes::{Area2D, PhysicsDirectSpaceState2D, PhysicsPointQueryParameters2D, Resource},
+        classes::{
+            Area2D, PhysicsDirectSpaceState2D, PhysicsPointQueryParameters2D,
+            PhysicsRayQueryParameters2D, ProjectSettings, Resource,
+        },
         prelude::*,
     };
     use std::ops::DerefMut;
@@ -155,20 +970,33 @@ pub mod inner2d {
     gravity_query! {
         GravityQuery2D where {
             [flags_2d_physics],
-            PhysicsPointQueryParameters2D,
+            PhysicsPointQueryParameters2D | PhysicsRayQueryParameters2D,
             PhysicsDirectSpaceState2D,
             DynGravityField2D as {
                 Area2D,
-                Vector2
+                Vector2,
+                PackedVector2Array
             }
         }
     }
 }
 
 pub mod inner3d {
-    use crate::gravity::{Field, Level, Mask};
+    use super::{
+        BlendMode, Candidate, Selection, average_samples, blend_gravity, found_field,
+        resolve_gravity,
+    };
+    use crate::gravity::{
+        Channel, Field, Level, Mask, channel_mask,
+        util::util3d::{orthonormal_basis, sample_offsets, shortest_arc},
+    };
     use godot::{
-        classes::{Area3D, PhysicsDirectSpaceState3D, PhysicsPointQueryParameters3D, Resource},
+        classes::{
+            Area3D, ArrayMesh, PhysicsDirectSpaceState3D, PhysicsPointQueryParameters3D,
+            PhysicsRayQueryParameters3D, ProjectSettings, Resource,
+            mesh::{ArrayType, PrimitiveType},
+        },
+        obj::EngineEnum,
         prelude::*,
     };
     use std::ops::DerefMut;
@@ -176,12 +1004,169 @@ pub mod inner3d {
     gravity_query! {
         GravityQuery3D where {
             [flags_3d_physics],
-            PhysicsPointQueryParameters3D,
+            PhysicsPointQueryParameters3D | PhysicsRayQueryParameters3D,
             PhysicsDirectSpaceState3D,
             DynGravityField3D as {
                 Area3D,
-                Vector3
+                Vector3,
+                PackedVector3Array
+            }
+        }
+    }
+
+    impl GravityQuery3D {
+        /// Find the orientation matching the gravity at the given position,
+        /// the forward axis is kept as close as possible to the reference.
+        pub fn gravity_basis(
+            &self,
+            space: &mut PhysicsDirectSpaceState3D,
+            position: &Vector3,
+            reference_forward: &Vector3,
+        ) -> Option<Basis> {
+            self.gravity_direction(space, position)
+                .filter(|(up, _)| !up.is_zero_approx())
+                .map(|(up, _)| orthonormal_basis(&up, reference_forward))
+        }
+
+        /// Find the downhill direction along a surface at the given position.
+        /// The gravity UP is projected onto the tangent plane of the surface and negated.
+        /// Return zero if the gravity is parallel to the normal (flat ground).
+        pub fn descent_direction(
+            &self,
+            space: &mut PhysicsDirectSpaceState3D,
+            position: &Vector3,
+            surface_normal: &Vector3,
+        ) -> Vector3 {
+            match self.gravity_direction(space, position) {
+                Some((up, _)) => {
+                    let normal = surface_normal.normalized_or_zero();
+                    -(up - normal * up.dot(normal)).normalized_or_zero()
+                }
+                None => Vector3::ZERO,
+            }
+        }
+
+        /// Find the shortest rotation bringing `current_up` onto the gravity UP direction.
+        /// Return the identity if no gravity is found.
+        pub fn orientation_delta(
+            &self,
+            space: &mut PhysicsDirectSpaceState3D,
+            position: &Vector3,
+            current_up: &Vector3,
+        ) -> Quaternion {
+            match self.gravity_direction(space, position) {
+                Some((up, _)) => shortest_arc(current_up, &up),
+                None => Quaternion::IDENTITY,
+            }
+        }
+
+        /// Sample the gravity at the center of each cell of a grid covering the region.
+        /// Return the position and the UP direction of each cell, cells are ordered
+        /// along X first, then Y and then Z. Positions without gravity report zero.
+        pub fn sample_grid(
+            &self,
+            space: &mut PhysicsDirectSpaceState3D,
+            region: &Aabb,
+            resolution: &Vector3i,
+        ) -> Vec<(Vector3, Vector3)> {
+            let resolution = Vector3i::new(
+                resolution.x.max(1),
+                resolution.y.max(1),
+                resolution.z.max(1),
+            );
+            let cell = region.size / resolution.cast_float();
+            let mut samples =
+                Vec::with_capacity((resolution.x * resolution.y * resolution.z) as usize);
+            for z in 0..resolution.z {
+                for y in 0..resolution.y {
+                    for x in 0..resolution.x {
+                        let index = Vector3::new(x as real, y as real, z as real);
+                        let position = region.position + (index + Vector3::splat(0.5)) * cell;
+                        let up = self
+                            .gravity_direction(space, &position)
+                            .map_or(Vector3::ZERO, |(up, _)| up);
+                        samples.push((position, up));
+                    }
+                }
             }
+            samples
+        }
+    }
+
+    #[godot_api(secondary)]
+    impl GravityQuery3D {
+        /// Find the orientation matching the gravity at the given position.
+        /// Return the identity if no gravity is found.
+        #[func]
+        pub fn find_gravity_basis(
+            &self,
+            mut space: Gd<PhysicsDirectSpaceState3D>,
+            position: Vector3,
+            reference_forward: Vector3,
+        ) -> Basis {
+            self.gravity_basis(space.deref_mut(), &position, &reference_forward)
+                .unwrap_or(Basis::IDENTITY)
+        }
+
+        /// Find the downhill direction along a surface at the given position.
+        #[func]
+        pub fn surface_descent_direction(
+            &self,
+            mut space: Gd<PhysicsDirectSpaceState3D>,
+            position: Vector3,
+            surface_normal: Vector3,
+        ) -> Vector3 {
+            self.descent_direction(space.deref_mut(), &position, &surface_normal)
+        }
+
+        /// Find the shortest rotation bringing `current_up` onto the gravity UP direction.
+        #[func]
+        pub fn gravity_orientation_delta(
+            &self,
+            mut space: Gd<PhysicsDirectSpaceState3D>,
+            position: Vector3,
+            current_up: Vector3,
+        ) -> Quaternion {
+            self.orientation_delta(space.deref_mut(), &position, &current_up)
+        }
+
+        /// Bake a mesh of arrows showing the gravity direction sampled on a grid
+        /// covering the region. The arrows point DOWN and are `arrow_scale` long.
+        /// Intended as an editor or offline tool, the mesh can be saved as a resource.
+        #[func]
+        pub fn bake_gravity_field_mesh(
+            &self,
+            mut space: Gd<PhysicsDirectSpaceState3D>,
+            region: Aabb,
+            resolution: Vector3i,
+            arrow_scale: real,
+        ) -> Gd<ArrayMesh> {
+            let samples = self.sample_grid(space.deref_mut(), &region, &resolution);
+
+            // draw a shaft and a two-sided head for each sample
+            let mut vertices = PackedVector3Array::new();
+            for (position, up) in samples {
+                if up.is_zero_approx() {
+                    continue;
+                }
+                let tip = position - up * arrow_scale;
+                let side = orthonormal_basis(&up, &Vector3::FORWARD).col_a() * (arrow_scale * 0.25);
+                let back = tip + up * (arrow_scale * 0.25);
+                for (from, to) in [(position, tip), (tip, back + side), (tip, back - side)] {
+                    vertices.push(from);
+                    vertices.push(to);
+                }
+            }
+
+            // build a mesh made of lines
+            let mut mesh = ArrayMesh::new_gd();
+            if !vertices.is_empty() {
+                let mut arrays = VariantArray::new();
+                arrays.resize(ArrayType::MAX.ord() as usize, &Variant::nil());
+                arrays.set(ArrayType::VERTEX.ord() as usize, &vertices.to_variant());
+                mesh.add_surface_from_arrays(PrimitiveType::LINES, &arrays);
+            }
+            mesh
         }
     }
 }
@@ -189,3 +1174,252 @@ pub mod inner3d {
 // re-export types
 pub use inner2d::{DynGravityField2D, GravityQuery2D};
 pub use inner3d::{DynGravityField3D, GravityQuery3D};
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        BlendMode, Candidate, Selection, average_samples, blend_gravity, found_field,
+        resolve_gravity,
+    };
+    use crate::gravity::{channel_mask, util::util3d::sample_offsets};
+    use godot::builtin::{Vector3, real};
+
+    /// Default settings of a query
+    const SELECTION: Selection = Selection {
+        blend_mode: BlendMode::Sum,
+        priority_blend_range: 0,
+        strict_priority: false,
+        channel: None,
+        overlap_strength_scale: 0.0,
+    };
+
+    /// Candidate field of the given identifier and level
+    fn candidate(id: i64, level: i32, up: Vector3, strength: real) -> Candidate<Vector3> {
+        Candidate {
+            id,
+            level,
+            channel: 0,
+            occluded: false,
+            up,
+            strength,
+            distance: id as real,
+        }
+    }
+
+    /// Select and blend the candidates
+    fn blend(selection: &Selection, candidates: &[Candidate<Vector3>]) -> (Vector3, real) {
+        let selected = selection.select(candidates);
+        blend_gravity(
+            selection.blend_mode,
+            selected.into_iter().map(|(index, weight)| {
+                let candidate = &candidates[index];
+                (candidate.up, candidate.strength, weight)
+            }),
+        )
+    }
+
+    #[test]
+    fn rest_up_applies_at_center_origin() {
+        // a body exactly at the origin of a center field has no UP direction
+        let up = Vector3::ZERO.normalized_or_zero();
+        let (gravity, strength) = blend_gravity(BlendMode::Sum, [(up, 2.0, 1.0)]);
+        assert_eq!(gravity, Vector3::ZERO);
+        assert_eq!(strength, 2.0);
+
+        let resolved = resolve_gravity(gravity, strength, Vector3::UP * 5.0, 0.0);
+        assert_eq!(resolved, Vector3::UP * 2.0);
+    }
+
+    #[test]
+    fn rest_up_applies_when_fields_cancel_out() {
+        let fields = [(Vector3::UP, 1.0, 1.0), (Vector3::DOWN, 1.0, 1.0)];
+        for blend_mode in [BlendMode::Sum, BlendMode::DistanceWeighted] {
+            let (gravity, strength) = blend_gravity(blend_mode, fields);
+            let resolved = resolve_gravity(gravity, strength, Vector3::RIGHT, 0.0);
+            assert!(resolved.length() > 0.0);
+            assert_eq!(resolved.normalized(), Vector3::RIGHT);
+        }
+    }
+
+    #[test]
+    fn blended_gravity_is_kept_above_threshold() {
+        let (gravity, strength) = blend_gravity(BlendMode::Sum, [(Vector3::UP, 3.0, 1.0)]);
+        assert_eq!(
+            resolve_gravity(gravity, strength, Vector3::RIGHT, 0.5),
+            Vector3::UP * 3.0
+        );
+    }
+
+    #[test]
+    fn blend_does_not_depend_on_discovery_order() {
+        let candidates = [
+            candidate(7, 0, Vector3::new(0.1, 0.7, 0.3).normalized(), 0.1),
+            candidate(2, 0, Vector3::new(-0.3, 0.2, 0.9).normalized(), 0.7),
+            candidate(5, 0, Vector3::new(0.6, -0.1, 0.2).normalized(), 0.3),
+            candidate(3, 0, Vector3::new(0.2, 0.4, -0.8).normalized(), 0.9),
+        ];
+        let orders = [
+            [0, 1, 2, 3],
+            [3, 2, 1, 0],
+            [1, 3, 0, 2],
+            [2, 0, 3, 1],
+            [3, 0, 2, 1],
+            [1, 2, 3, 0],
+        ];
+        for blend_mode in [
+            BlendMode::Sum,
+            BlendMode::DistanceWeighted,
+            BlendMode::Nearest,
+        ] {
+            let selection = Selection {
+                blend_mode,
+                ..SELECTION
+            };
+            let expected = blend(&selection, &candidates);
+            for order in orders {
+                let shuffled = order.map(|index| candidates[index]);
+                let (gravity, strength) = blend(&selection, &shuffled);
+                assert_eq!(gravity.x.to_bits(), expected.0.x.to_bits());
+                assert_eq!(gravity.y.to_bits(), expected.0.y.to_bits());
+                assert_eq!(gravity.z.to_bits(), expected.0.z.to_bits());
+                assert_eq!(strength.to_bits(), expected.1.to_bits());
+            }
+        }
+    }
+
+    #[test]
+    fn selection_keeps_the_highest_level() {
+        let candidates = [
+            candidate(1, 2, Vector3::UP, 1.0),
+            candidate(2, 1, Vector3::RIGHT, 1.0),
+            candidate(3, 2, Vector3::BACK, 1.0),
+        ];
+        let selected = SELECTION.select(&candidates);
+        assert_eq!(selected, [(0, 1.0), (2, 1.0)]);
+    }
+
+    #[test]
+    fn channel_ignores_fields_of_other_channels() {
+        // both fields share the collision bit, only the channel tells them apart
+        let candidates = [
+            Candidate {
+                channel: 2,
+                level: 5,
+                ..candidate(1, 0, Vector3::RIGHT, 1.0)
+            },
+            Candidate {
+                channel: 1,
+                ..candidate(2, 0, Vector3::UP, 1.0)
+            },
+        ];
+        let selection = Selection {
+            channel: Some(1),
+            ..SELECTION
+        };
+        assert_eq!(selection.select(&candidates), [(1, 1.0)]);
+        assert_eq!(blend(&selection, &candidates), (Vector3::UP, 1.0));
+
+        // without a channel the higher level wins
+        assert_eq!(SELECTION.select(&candidates), [(0, 1.0)]);
+    }
+
+    #[test]
+    fn channels_map_to_distinct_collision_bits() {
+        assert_eq!(channel_mask(0), 0b1);
+        assert_eq!(channel_mask(1), 0b10);
+        assert_eq!(channel_mask(2), 0b100);
+        assert_eq!(channel_mask(31), 1 << 31);
+        assert_eq!(channel_mask(40), 1 << 31);
+    }
+
+    #[test]
+    fn occluder_cuts_the_gravity_of_a_field() {
+        // a wall stands between the center field and the position
+        let center = Candidate {
+            occluded: true,
+            ..candidate(1, 1, Vector3::UP, 1.0)
+        };
+        let flat = candidate(2, 0, Vector3::RIGHT, 1.0);
+        assert!(SELECTION.select(&[center]).is_empty());
+        assert_eq!(blend(&SELECTION, &[center]), (Vector3::ZERO, 0.0));
+
+        // the hidden field does not prevent the lower levels from applying
+        assert_eq!(blend(&SELECTION, &[center, flat]), (Vector3::RIGHT, 1.0));
+
+        // without the wall the center field wins
+        let visible = Candidate {
+            occluded: false,
+            ..center
+        };
+        assert_eq!(blend(&SELECTION, &[visible, flat]), (Vector3::UP, 1.0));
+    }
+
+    #[test]
+    fn strength_ramps_with_the_overlapping_fields() {
+        let selection = Selection {
+            overlap_strength_scale: 0.5,
+            ..SELECTION
+        };
+        let fields: Vec<_> = (1..=3)
+            .map(|id| candidate(id, 0, Vector3::UP, 1.0))
+            .collect();
+
+        // a lower level field does not count as overlapping
+        let lower = candidate(4, -1, Vector3::UP, 1.0);
+        for (count, expected) in [(1, 1.0), (2, 1.5), (3, 2.0)] {
+            let mut candidates = fields[..count].to_vec();
+            candidates.push(lower);
+            assert_eq!(selection.overlap_factor(&candidates), expected);
+            assert_eq!(SELECTION.overlap_factor(&candidates), 1.0);
+        }
+    }
+
+    #[test]
+    fn averaging_smooths_a_field_boundary() {
+        // the gravity changes abruptly across the plane X = 0
+        let gravity_at = |position: Vector3| {
+            let up = if position.x < 0.0 {
+                Vector3::UP
+            } else {
+                Vector3::RIGHT
+            };
+            (up, 1.0)
+        };
+        let averaged = |position: Vector3| {
+            average_samples(
+                sample_offsets(16, 0.5)
+                    .into_iter()
+                    .map(|offset| gravity_at(position + offset)),
+            )
+            .expect("every sample finds a field")
+            .0
+        };
+
+        let (before, after) = (Vector3::new(-0.01, 0.0, 0.0), Vector3::new(0.01, 0.0, 0.0));
+        let single_jump = gravity_at(before).0.distance_to(gravity_at(after).0);
+        let averaged_jump = averaged(before).distance_to(averaged(after));
+        assert!(single_jump > 1.0);
+        assert!(averaged_jump < 0.2, "averaged jump {averaged_jump}");
+    }
+
+    #[test]
+    fn averaging_without_samples_finds_nothing() {
+        assert_eq!(average_samples::<Vector3>([]), None);
+        assert_eq!(
+            average_samples([(Vector3::UP, 1.0), (Vector3::RIGHT, 3.0)]),
+            Some((Vector3::new(0.5, 0.5, 0.0), 2.0))
+        );
+    }
+
+    #[test]
+    fn has_gravity_only_with_an_overlapping_field() {
+        // nothing found, or only colliders which are not gravity fields
+        assert!(!found_field::<()>([]));
+        assert!(!found_field::<()>([None]));
+
+        // a gravity field overlaps the position, even behind a plain body
+        assert!(found_field([Some(())]));
+        assert!(found_field([None, Some(())]));
+        assert!(found_field([None, None, Some(())]));
+    }
+}
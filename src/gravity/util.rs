@@ -19,25 +19,75 @@ macro_rules! unit {
     };
 }
 
+use godot::{
+    builtin::{Array, StringName},
+    classes::Node,
+    obj::Gd,
+};
+
+/// Check if a node belongs to any of the groups, an empty list matches any node.
+#[inline]
+pub(crate) fn in_any_group(groups: &Array<StringName>, node: &Gd<Node>) -> bool {
+    groups.is_empty() || groups.iter_shared().any(|group| node.is_in_group(&group))
+}
+
 pub mod util2d {
 
-    use crate::gravity::{Field, build_trs::Basis2};
-    use godot::{builtin::*, classes::Area2D, global::is_zero_approx, obj::WithBaseField};
+    use crate::gravity::{Field, axis::Axis2D, build_trs::Basis2};
+    use godot::{
+        builtin::*,
+        classes::{Area2D, MultiMesh, multi_mesh::TransformFormat},
+        global::is_zero_approx,
+        obj::{Gd, WithBaseField},
+    };
 
     /// Define a minimal 2D vector
     pub const MIN_SIZE: Vector2 = Vector2::new(real::MIN_POSITIVE, real::MIN_POSITIVE);
 
-    /// Get the UP direction in global space based on the local UP direction.
+    /// Get the UP direction in global space for a position in global space,
+    /// the position is brought into the local space of the field first.
     #[inline]
     pub(crate) fn global_direction<F>(spatial: &F, position: &Vector2) -> Vector2
     where
         F: Field<Vector2> + WithBaseField<Base = Area2D>,
     {
         spatial
-            .local_up(position)
+            .local_up(&local_position(spatial, position))
             .rotated(spatial.base().get_global_rotation())
     }
 
+    /// Bring a position in global space into the local space of the field.
+    #[inline]
+    pub(crate) fn local_position<F>(spatial: &F, position: &Vector2) -> Vector2
+    where
+        F: WithBaseField<Base = Area2D>,
+    {
+        spatial.base().get_global_transform().affine_inverse() * *position
+    }
+
+    /// Get the center of the gravity field in global space.
+    #[inline]
+    pub(crate) fn global_center<F>(spatial: &F) -> Vector2
+    where
+        F: Field<Vector2> + WithBaseField<Base = Area2D>,
+    {
+        spatial.base().get_global_position()
+    }
+
+    /// Get the bounding rectangle in global space of a local box
+    /// defined by its center and half extents.
+    pub(crate) fn global_bounds<F>(spatial: &F, center: Vector2, half_extents: Vector2) -> Rect2
+    where
+        F: WithBaseField<Base = Area2D>,
+    {
+        let trs = spatial.base().get_global_transform();
+        let half = Vector2::new(
+            trs.a.x.abs() * half_extents.x + trs.b.x.abs() * half_extents.y,
+            trs.a.y.abs() * half_extents.x + trs.b.y.abs() * half_extents.y,
+        );
+        Rect2::new(trs * center - half, half * 2.0)
+    }
+
     /// Flatten a vector along the X-axis
     #[inline]
     pub fn flatten_x(v: &Vector2) -> Vector2 {
@@ -50,6 +100,15 @@ pub mod util2d {
         Vector2::new(v.x, 0.0)
     }
 
+    /// Flatten a vector along the selected axis
+    #[inline]
+    pub fn flatten(v: &Vector2, axis: Axis2D) -> Vector2 {
+        match axis {
+            Axis2D::X | Axis2D::NegX => flatten_x(v),
+            Axis2D::Y | Axis2D::NegY => flatten_y(v),
+        }
+    }
+
     /// Return true if the angle between the two vectors is acute
     #[inline]
     pub fn is_acute(a: &Vector2, b: &Vector2) -> bool {
@@ -68,6 +127,25 @@ pub mod util2d {
         a.dot(*b) < 0.0
     }
 
+    /// Fill the multimesh with one instance per transform
+    pub fn populate_multimesh(multimesh: &mut Gd<MultiMesh>, transforms: &[Transform2D]) {
+        // the transform format can only be changed without instances
+        multimesh.set_instance_count(0);
+        multimesh.set_transform_format(TransformFormat::TRANSFORM_2D);
+        multimesh.set_instance_count(transforms.len() as i32);
+        for (i, trs) in transforms.iter().enumerate() {
+            multimesh.set_instance_transform_2d(i as i32, *trs);
+        }
+    }
+
+    /// Distribute points evenly on a circle of the given radius
+    pub fn sample_offsets(count: usize, radius: real) -> Vec<Vector2> {
+        let step = real_consts::TAU / count.max(1) as real;
+        (0..count)
+            .map(|i| Vector2::from_angle(step * i as real) * radius)
+            .collect()
+    }
+
     /// Basis axis-aligned orientations for capsule shapes
     pub const ROT_X: Basis2 = [Vector2::new(0.0, 1.0), Vector2::new(-1.0, 0.0)];
     pub const ROT_Y: Basis2 = [Vector2::new(1.0, 0.0), Vector2::new(0.0, 1.0)];
@@ -78,21 +156,56 @@ pub mod util3d {
     use crate::gravity::{Field, axis::Axis3D};
     use godot::{
         builtin::{math::FloatExt, *},
-        classes::Area3D,
-        obj::WithBaseField,
+        classes::{Area3D, MultiMesh, multi_mesh::TransformFormat},
+        obj::{Gd, WithBaseField},
     };
 
     /// Define a minimal 2D vector
     pub const MIN_SIZE: Vector3 =
         Vector3::new(real::MIN_POSITIVE, real::MIN_POSITIVE, real::MIN_POSITIVE);
 
-    /// Get the UP direction in global space based on the local UP direction.
+    /// Get the UP direction in global space for a position in global space,
+    /// the position is brought into the local space of the field first.
     #[inline]
     pub(crate) fn global_direction<F>(spatial: &F, position: &Vector3) -> Vector3
     where
         F: Field<Vector3> + WithBaseField<Base = Area3D>,
     {
-        spatial.base().get_global_basis() * spatial.local_up(position)
+        spatial.base().get_global_basis() * spatial.local_up(&local_position(spatial, position))
+    }
+
+    /// Bring a position in global space into the local space of the field.
+    #[inline]
+    pub(crate) fn local_position<F>(spatial: &F, position: &Vector3) -> Vector3
+    where
+        F: WithBaseField<Base = Area3D>,
+    {
+        spatial.base().get_global_transform().affine_inverse() * *position
+    }
+
+    /// Get the center of the gravity field in global space.
+    #[inline]
+    pub(crate) fn global_center<F>(spatial: &F) -> Vector3
+    where
+        F: Field<Vector3> + WithBaseField<Base = Area3D>,
+    {
+        spatial.base().get_global_position()
+    }
+
+    /// Get the bounding box in global space of a local box
+    /// defined by its center and half extents.
+    pub(crate) fn global_bounds<F>(spatial: &F, center: Vector3, half_extents: Vector3) -> Aabb
+    where
+        F: WithBaseField<Base = Area3D>,
+    {
+        let trs = spatial.base().get_global_transform();
+        let [x, y, z] = trs.basis.rows;
+        let half = Vector3::new(
+            x.abs().dot(half_extents),
+            y.abs().dot(half_extents),
+            z.abs().dot(half_extents),
+        );
+        Aabb::new(trs * center - half, half * 2.0)
     }
 
     /// Flatten a vector along the X-axis
@@ -113,6 +226,16 @@ pub mod util3d {
         Vector3::new(v.x, v.y, 0.0)
     }
 
+    /// Flatten a vector along the selected axis
+    #[inline]
+    pub fn flatten(v: &Vector3, axis: Axis3D) -> Vector3 {
+        match axis {
+            Axis3D::X | Axis3D::NegX => flatten_x(v),
+            Axis3D::Y | Axis3D::NegY => flatten_y(v),
+            Axis3D::Z | Axis3D::NegZ => flatten_z(v),
+        }
+    }
+
     /// Return true if the angle between the two vectors is acute
     #[inline]
     pub fn is_acute(a: &Vector3, b: &Vector3) -> bool {
@@ -131,6 +254,85 @@ pub mod util3d {
         a.dot(*b) < 0.0
     }
 
+    /// Fill the multimesh with one instance per transform
+    pub fn populate_multimesh(multimesh: &mut Gd<MultiMesh>, transforms: &[Transform3D]) {
+        // the transform format can only be changed without instances
+        multimesh.set_instance_count(0);
+        multimesh.set_transform_format(TransformFormat::TRANSFORM_3D);
+        multimesh.set_instance_count(transforms.len() as i32);
+        for (i, trs) in transforms.iter().enumerate() {
+            multimesh.set_instance_transform(i as i32, *trs);
+        }
+    }
+
+    /// Build an orthonormal basis whose Y-axis is the UP direction,
+    /// the forward axis (-Z) is kept as close as possible to the reference.
+    /// If the reference is parallel to UP, an arbitrary forward axis is picked.
+    pub fn orthonormal_basis(up: &Vector3, reference_forward: &Vector3) -> Basis {
+        let up = up.normalized_or_zero();
+
+        // Remove the UP component of the reference (Gram-Schmidt)
+        let mut forward = *reference_forward - up * up.dot(*reference_forward);
+        if forward.is_zero_approx() {
+            // pick any axis which is not parallel to UP
+            let fallback = if up.x.abs() < 0.9 {
+                Vector3::RIGHT
+            } else {
+                Vector3::BACK
+            };
+            forward = fallback - up * up.dot(fallback);
+        }
+        let back = -forward.normalized();
+        Basis::from_cols(up.cross(back), up, back)
+    }
+
+    /// Compute the shortest arc rotation bringing `from` onto `to`.
+    /// When the vectors are opposite, a stable axis perpendicular to `from` is used.
+    pub fn shortest_arc(from: &Vector3, to: &Vector3) -> Quaternion {
+        const EPSILON: real = 1e-6;
+        let (from, to) = (from.normalized_or_zero(), to.normalized_or_zero());
+        if from.is_zero_approx() || to.is_zero_approx() {
+            return Quaternion::IDENTITY;
+        }
+
+        let dot = from.dot(to);
+        if dot >= 1.0 - EPSILON {
+            // already aligned
+            Quaternion::IDENTITY
+        } else if dot <= -1.0 + EPSILON {
+            // opposite directions, any perpendicular axis works
+            let axis = orthonormal_basis(&from, &Vector3::FORWARD).col_a();
+            Quaternion::from_axis_angle(axis, real_consts::PI)
+        } else {
+            let axis = from.cross(to).normalized();
+            Quaternion::from_axis_angle(axis, dot.acos())
+        }
+    }
+
+    /// Find the closest point to the position on a circle centered on the origin
+    /// and perpendicular to the given axis. Any point of the circle is equally
+    /// close to a position on the axis, the center is then returned.
+    #[inline]
+    pub fn closest_point_on_circle(position: &Vector3, axis: Axis3D, radius: real) -> Vector3 {
+        flatten(position, axis).normalized_or_zero() * radius
+    }
+
+    /// Distribute points evenly on a sphere of the given radius
+    /// using a Fibonacci lattice.
+    pub fn sample_offsets(count: usize, radius: real) -> Vec<Vector3> {
+        // golden angle in radians
+        let golden = real_consts::PI * (3.0 - (5.0 as real).sqrt());
+        let count = count.max(1);
+        (0..count)
+            .map(|i| {
+                let y = 1.0 - 2.0 * (i as real + 0.5) / count as real;
+                let r = (1.0 - y * y).sqrt();
+                let theta = golden * i as real;
+                Vector3::new(theta.cos() * r, y, theta.sin() * r) * radius
+            })
+            .collect()
+    }
+
     /// Basis axis-aligned orientations for capsule shapes
     pub const BASIS_X: Basis = axis_aligned_basis(Axis3D::Z, 1);
     pub const BASIS_Y: Basis = Basis::IDENTITY;
@@ -140,6 +342,11 @@ pub mod util3d {
     const fn axis_aligned_basis(axis: Axis3D, rot: i8) -> Basis {
         // Compute the values of sin and cos,
         // since the basis is axis-aligned, they are either -1, 0, 1.
+        // Rotating around a negative axis is rotating backward around the positive one
+        let rot = match axis {
+            Axis3D::NegX | Axis3D::NegY | Axis3D::NegZ => -rot,
+            Axis3D::X | Axis3D::Y | Axis3D::Z => rot,
+        };
         let rot = rot.rem_euclid(4);
         let (sin, cos) = match rot {
             0 => (0.0, 1.0),  //   0
@@ -151,17 +358,17 @@ pub mod util3d {
 
         // Select the euler angle
         let rows = match axis {
-            Axis3D::X => [
+            Axis3D::X | Axis3D::NegX => [
                 Vector3::new(1.0, 0.0, 0.0),
                 Vector3::new(0.0, cos, -sin),
                 Vector3::new(0.0, sin, cos),
             ],
-            Axis3D::Y => [
+            Axis3D::Y | Axis3D::NegY => [
                 Vector3::new(cos, 0.0, sin),
                 Vector3::new(0.0, 1.0, 0.0),
                 Vector3::new(-sin, 0.0, cos),
             ],
-            Axis3D::Z => [
+            Axis3D::Z | Axis3D::NegZ => [
                 Vector3::new(cos, -sin, 0.0),
                 Vector3::new(sin, cos, 0.0),
                 Vector3::new(0.0, 0.0, 1.0),
@@ -170,3 +377,45 @@ pub mod util3d {
         Basis::from_rows(rows[0], rows[1], rows[2])
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::util3d::orthonormal_basis;
+    use godot::builtin::{Basis, Vector3, math::ApproxEq};
+
+    /// Assert the basis is orthonormal and keeps its handedness
+    #[track_caller]
+    fn assert_orthonormal(basis: &Basis) {
+        let (a, b, c) = (basis.col_a(), basis.col_b(), basis.col_c());
+        assert!(a.is_normalized() && b.is_normalized() && c.is_normalized());
+        assert!(a.dot(b).abs() < 1e-5 && b.dot(c).abs() < 1e-5 && c.dot(a).abs() < 1e-5);
+        assert!((basis.determinant() - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn gravity_basis_is_orthonormal_and_up_aligned() {
+        let up = Vector3::new(0.3, 2.0, -0.5);
+        let reference = Vector3::new(1.0, 0.2, -1.0);
+        let basis = orthonormal_basis(&up, &reference);
+        assert_orthonormal(&basis);
+        assert!(basis.col_b().approx_eq(&up.normalized()));
+
+        // the forward axis is the reference without its UP component
+        let forward = -basis.col_c();
+        let expected = (reference - up.normalized() * up.normalized().dot(reference)).normalized();
+        assert!(forward.approx_eq(&expected));
+    }
+
+    #[test]
+    fn gravity_basis_with_a_reference_parallel_to_up() {
+        for (up, reference) in [
+            (Vector3::UP, Vector3::UP),
+            (Vector3::UP, Vector3::DOWN * 3.0),
+            (Vector3::RIGHT, Vector3::LEFT),
+        ] {
+            let basis = orthonormal_basis(&up, &reference);
+            assert_orthonormal(&basis);
+            assert!(basis.col_b().approx_eq(&up));
+        }
+    }
+}